@@ -1,3 +1,83 @@
+use std::path::Path;
+
+/// Artifacts staged into `artifacts/` by CI, compressed into OUT_DIR for embedding.
+const ARTIFACTS: &[&str] = &[
+    "uprooted_profiler.dll",
+    "libuprooted_profiler.so",
+    "UprootedHook.dll",
+    "UprootedHook.deps.json",
+    "uprooted-preload.js",
+    "uprooted.css",
+];
+
+/// ARM64 profiler builds. Missing ones are embedded as empty files.
+const OPTIONAL_ARTIFACTS: &[&str] = &["uprooted_profiler_arm64.dll", "libuprooted_profiler_arm64.so"];
+
+fn compress_artifacts() {
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    println!("cargo:rerun-if-changed=artifacts");
+
+    for name in ARTIFACTS {
+        let src = Path::new("artifacts").join(name);
+        println!("cargo:rerun-if-changed={}", src.display());
+        // Only the profiler for the target OS is staged
+        let Ok(data) = std::fs::read(&src) else {
+            continue;
+        };
+        let compressed = zstd::bulk::compress(&data, 19).expect("failed to compress artifact");
+        std::fs::write(Path::new(&out_dir).join(format!("{}.zst", name)), compressed)
+            .expect("failed to write compressed artifact");
+    }
+
+    for name in OPTIONAL_ARTIFACTS {
+        let src = Path::new("artifacts").join(name);
+        println!("cargo:rerun-if-changed={}", src.display());
+        let compressed = match std::fs::read(&src) {
+            Ok(data) => zstd::bulk::compress(&data, 19).expect("failed to compress artifact"),
+            Err(_) => Vec::new(),
+        };
+        std::fs::write(Path::new(&out_dir).join(format!("{}.zst", name)), compressed)
+            .expect("failed to write compressed artifact");
+    }
+}
+
+/// Version of each web artifact, from `artifacts/<name>.VERSION` when CI stages one,
+/// otherwise the installer version. Read by `src/artifact_meta.rs`.
+fn artifact_versions() {
+    let crate_version = std::env::var("CARGO_PKG_VERSION").expect("CARGO_PKG_VERSION is set by cargo");
+    for (name, var) in [
+        ("uprooted-preload.js", "UPROOTED_PRELOAD_JS_VERSION"),
+        ("uprooted.css", "UPROOTED_THEME_CSS_VERSION"),
+    ] {
+        let file = Path::new("artifacts").join(format!("{}.VERSION", name));
+        println!("cargo:rerun-if-changed={}", file.display());
+        let version = std::fs::read_to_string(&file)
+            .map(|v| v.trim().to_string())
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| crate_version.clone());
+        println!("cargo:rustc-env={}={}", var, version);
+    }
+}
+
 fn main() {
+    // Seed for the deployment manifest HMAC key (see integrity.rs)
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    println!("cargo:rustc-env=UPROOTED_BUILD_TIMESTAMP={}", timestamp);
+
+    // Oldest Root release the embedded profiler supports (see compat.rs)
+    println!("cargo:rerun-if-env-changed=UPROOTED_MIN_ROOT_VERSION");
+    let min_root = std::env::var("UPROOTED_MIN_ROOT_VERSION").unwrap_or_else(|_| "0.0.0".to_string());
+    println!("cargo:rustc-env=UPROOTED_MIN_ROOT_VERSION={}", min_root);
+
+    // Ed25519 key for artifact update manifests (see updates.rs), read with option_env!
+    println!("cargo:rerun-if-env-changed=UPROOTED_UPDATE_PUBLIC_KEY");
+
+    compress_artifacts();
+    artifact_versions();
+
     tauri_build::build()
 }
@@ -0,0 +1,22 @@
+//! Versions of the embedded web artifacts, set by `build.rs` from
+//! `artifacts/<name>.VERSION` or, when there is none, the installer version.
+
+use serde::Serialize;
+
+pub const PRELOAD_JS_VERSION: &str = env!("UPROOTED_PRELOAD_JS_VERSION");
+pub const THEME_CSS_VERSION: &str = env!("UPROOTED_THEME_CSS_VERSION");
+
+#[derive(Serialize)]
+pub struct ArtifactVersions {
+    pub installer: String,
+    pub preload_js: String,
+    pub theme_css: String,
+}
+
+pub fn get_artifact_versions() -> ArtifactVersions {
+    ArtifactVersions {
+        installer: env!("CARGO_PKG_VERSION").to_string(),
+        preload_js: PRELOAD_JS_VERSION.to_string(),
+        theme_css: THEME_CSS_VERSION.to_string(),
+    }
+}
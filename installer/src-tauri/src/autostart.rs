@@ -0,0 +1,69 @@
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+/// XDG autostart entry, for desktops without a systemd user session (XFCE, MATE, ...).
+#[cfg(target_os = "linux")]
+pub fn autostart_path() -> PathBuf {
+    let config = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config"));
+    config.join("autostart/uprooted-monitor.desktop")
+}
+
+#[cfg(target_os = "linux")]
+pub fn install_autostart() -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate installer binary: {}", e))?;
+
+    let content = format!(
+        "[Desktop Entry]\n\
+Type=Application\n\
+Name=Uprooted Monitor\n\
+Comment=Keeps Uprooted's hook files in place\n\
+Exec=\"{}\" --daemon\n\
+Hidden=false\n\
+NoDisplay=true\n\
+X-GNOME-Autostart-enabled=true\n",
+        exe.display()
+    );
+
+    let path = autostart_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(target_os = "linux")]
+pub fn remove_autostart() -> Result<(), String> {
+    let path = autostart_path();
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_autostart_enabled() -> bool {
+    autostart_path().exists()
+}
+
+#[cfg(target_os = "windows")]
+pub fn install_autostart() -> Result<(), String> {
+    Err("XDG autostart is only available on Linux.".to_string())
+}
+
+#[cfg(target_os = "windows")]
+pub fn remove_autostart() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_autostart_enabled() -> bool {
+    false
+}
@@ -0,0 +1,31 @@
+use semver::Version;
+
+/// Oldest Root release whose CoreCLR ABI the embedded profiler supports.
+/// Set at build time through the `UPROOTED_MIN_ROOT_VERSION` env var.
+pub const MINIMUM_ROOT_VERSION: &str = env!("UPROOTED_MIN_ROOT_VERSION");
+
+/// Parse a Root version string, tolerating a `v` prefix and missing minor/patch parts.
+pub fn parse_version(version: &str) -> Option<Version> {
+    let version = version.trim().trim_start_matches('v');
+    if let Ok(v) = Version::parse(version) {
+        return Some(v);
+    }
+    let mut parts = version.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some(Version::new(major, minor, patch))
+}
+
+/// Explain why `root_version` is too old, or `None` if it is supported or unknown.
+pub fn check_root_version(root_version: Option<&str>) -> Option<String> {
+    let installed = parse_version(root_version?)?;
+    let minimum = parse_version(MINIMUM_ROOT_VERSION)?;
+    if installed >= minimum {
+        return None;
+    }
+    Some(format!(
+        "Root {} is older than {}, the oldest version this Uprooted build supports. Update Root before installing.",
+        installed, minimum
+    ))
+}
@@ -0,0 +1,90 @@
+use lightningcss::error::{Error, ParserError};
+use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CssError {
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+}
+
+impl CssError {
+    fn from_parser_error(e: &Error<ParserError>) -> Self {
+        let (line, column) = e.loc.as_ref().map(|l| (l.line + 1, l.column)).unwrap_or((0, 0));
+        Self {
+            line,
+            column,
+            message: e.kind.to_string(),
+        }
+    }
+}
+
+/// Parse CSS and collect every syntax error instead of stopping at the first one.
+/// Line numbers are 1-based to match what the user sees in the editor.
+pub fn validate_css(css: &str) -> Result<(), Vec<CssError>> {
+    let warnings = Arc::new(RwLock::new(Vec::new()));
+    let options = ParserOptions {
+        filename: "custom.css".to_string(),
+        error_recovery: true,
+        warnings: Some(warnings.clone()),
+        ..ParserOptions::default()
+    };
+
+    let mut errors: Vec<CssError> = Vec::new();
+    if let Err(e) = StyleSheet::parse(css, options) {
+        errors.push(CssError::from_parser_error(&e));
+    }
+    if let Ok(warnings) = warnings.read() {
+        errors.extend(warnings.iter().map(CssError::from_parser_error));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        errors.sort_by_key(|e| (e.line, e.column));
+        Err(errors)
+    }
+}
+
+/// Minify a stylesheet. Fails if the input does not parse.
+pub fn minify_css(input: &str) -> Result<String, String> {
+    let mut sheet = StyleSheet::parse(input, ParserOptions::default())
+        .map_err(|e| format!("Failed to parse CSS: {}", e))?;
+    sheet
+        .minify(MinifyOptions::default())
+        .map_err(|e| format!("Failed to minify CSS: {}", e))?;
+    let out = sheet
+        .to_css(PrinterOptions {
+            minify: true,
+            ..PrinterOptions::default()
+        })
+        .map_err(|e| format!("Failed to print CSS: {}", e))?;
+    Ok(out.code)
+}
+
+#[derive(Serialize)]
+pub struct BenchmarkResult {
+    pub original_bytes: usize,
+    pub minified_bytes: usize,
+    pub iterations: u32,
+    pub average_micros: u64,
+}
+
+/// Time repeated minification of the embedded theme stylesheet.
+pub fn benchmark_minification(input: &str, iterations: u32) -> Result<BenchmarkResult, String> {
+    let iterations = iterations.max(1);
+    let mut minified_bytes = 0;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        minified_bytes = minify_css(input)?.len();
+    }
+    Ok(BenchmarkResult {
+        original_bytes: input.len(),
+        minified_bytes,
+        iterations,
+        average_micros: (start.elapsed().as_micros() / iterations as u128) as u64,
+    })
+}
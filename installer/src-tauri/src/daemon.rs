@@ -0,0 +1,78 @@
+use crate::hook;
+use crossbeam_channel::{select, tick, unbounded, Sender};
+use std::time::Duration;
+
+/// How often the headless monitor re-checks the deployed files.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the Root process list is polled.
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long after Root starts before its environment is checked for the hook.
+const HOOK_CHECK_DELAY: Duration = Duration::from_secs(5);
+
+enum MonitorEvent {
+    RootStarted,
+    RootStopped,
+}
+
+fn watch_root_process(events: Sender<MonitorEvent>) {
+    let mut was_running = hook::check_root_running();
+    loop {
+        std::thread::sleep(PROCESS_POLL_INTERVAL);
+        let running = hook::check_root_running();
+        if running != was_running {
+            let event = if running {
+                MonitorEvent::RootStarted
+            } else {
+                MonitorEvent::RootStopped
+            };
+            if events.send(event).is_err() {
+                return;
+            }
+            was_running = running;
+        }
+    }
+}
+
+/// Whether Root, just started, is running without the hook even though uprooted
+/// is installed.
+fn started_without_hook() -> bool {
+    std::thread::sleep(HOOK_CHECK_DELAY);
+    hook::check_root_running()
+        && hook::detect_launch_method() == hook::LaunchMethod::Direct
+        && hook::check_hook_status().env_ok
+}
+
+/// Watch Root's process. Runs `check` on a fixed interval and whenever Root exits,
+/// so files are back in place before its next launch, and `on_hook_inactive` when
+/// Root starts without the hook env vars. Used by the headless `--daemon` mode and
+/// in the background of the GUI.
+pub fn start_process_monitor(check: impl Fn(), on_hook_inactive: impl Fn()) {
+    tracing::info!("Starting monitor (interval {:?})", CHECK_INTERVAL);
+
+    let (tx, events) = unbounded();
+    std::thread::spawn(move || watch_root_process(tx));
+    let ticker = tick(CHECK_INTERVAL);
+
+    check();
+    loop {
+        select! {
+            recv(ticker) -> _ => check(),
+            recv(events) -> event => match event {
+                Ok(MonitorEvent::RootStarted) => {
+                    tracing::info!("Root started");
+                    if started_without_hook() {
+                        tracing::warn!("Root is running without the hook env vars");
+                        on_hook_inactive();
+                    }
+                }
+                Ok(MonitorEvent::RootStopped) => {
+                    tracing::info!("Root exited; checking deployed files");
+                    check();
+                }
+                Err(_) => return,
+            },
+        }
+    }
+}
@@ -1,7 +1,14 @@
+use crate::compat;
+use crate::encoding;
 use crate::hook::{self, HookStatus};
-use serde::Serialize;
+use crate::integrity;
+use crate::receipt;
+use crate::settings;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::patcher;
 
@@ -12,11 +19,54 @@ pub struct DetectionResult {
     pub profile_dir: String,
     pub html_files: Vec<String>,
     pub is_installed: bool,
+    pub is_snap: bool,
     pub hook_status: HookStatus,
+    pub warnings: Vec<String>,
+    /// Installed Root version, when it can be determined.
+    pub root_version: Option<String>,
+    /// Set when `root_version` is below `compat::MINIMUM_ROOT_VERSION`.
+    pub compat_warning: Option<String>,
+    /// Whether the running Root has the hook env vars. `None` if Root isn't
+    /// running or its environment couldn't be read.
+    pub running_with_hook: Option<bool>,
+    pub root_layout: RootLayout,
+    pub update_mechanism: UpdateMechanism,
+    /// Root shipped a readable `RootApps/apps.json`, so app names come from it.
+    pub apps_manifest_found: bool,
 }
 
-#[cfg(target_os = "windows")]
+#[derive(Serialize, Clone)]
+pub struct HtmlFileInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified: String,
+    pub is_patched: bool,
+    pub injection_version: Option<String>,
+    pub backup_exists: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RootAppInfo {
+    pub app_dir: String,
+    pub index_html: String,
+    /// The name from `RootApps/apps.json`, else the page's `<title>`, else the directory
+    /// name. "Root Main UI" for WebRtcBundle.
+    pub app_name: String,
+    pub is_patched: bool,
+    pub injection_version: Option<String>,
+}
+
+/// Root's profile directory. `UPROOTED_PROFILE_DIR` overrides it (development and
+/// CI only), which lets tests point the installer at a scratch profile.
 pub fn get_profile_dir() -> PathBuf {
+    match std::env::var_os("UPROOTED_PROFILE_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => default_profile_dir(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn default_profile_dir() -> PathBuf {
     let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
     PathBuf::from(local_app_data)
         .join("Root Communications")
@@ -25,9 +75,21 @@ pub fn get_profile_dir() -> PathBuf {
         .join("default")
 }
 
+/// Snap package name Root is published under.
 #[cfg(target_os = "linux")]
-pub fn get_profile_dir() -> PathBuf {
+pub const SNAP_NAME: &str = "root-communications";
+
+#[cfg(target_os = "linux")]
+fn default_profile_dir() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_default();
+    // Snap confines app data to ~/snap/<name>/current
+    let snap_profile = PathBuf::from(&home)
+        .join("snap")
+        .join(SNAP_NAME)
+        .join("current/.local/share/Root Communications/Root/profile/default");
+    if snap_profile.exists() {
+        return snap_profile;
+    }
     PathBuf::from(home)
         .join(".local/share/Root Communications/Root/profile/default")
 }
@@ -51,6 +113,8 @@ pub fn get_root_exe_path() -> PathBuf {
         format!("{}/.local/bin/Root.AppImage", home),
         "/opt/Root.AppImage".to_string(),
         "/usr/bin/Root.AppImage".to_string(),
+        format!("/snap/{}/current/usr/bin/Root", SNAP_NAME),
+        format!("/usr/bin/{}", SNAP_NAME),
     ];
     for c in &candidates {
         let p = PathBuf::from(c);
@@ -67,6 +131,199 @@ pub fn get_root_exe_path() -> PathBuf {
     PathBuf::from(format!("{}/Applications/Root.AppImage", home))
 }
 
+/// Version of the installed Root, read from the package metadata next to the binary.
+#[cfg(target_os = "windows")]
+pub fn get_root_version() -> Option<String> {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+    let root_dir = PathBuf::from(local_app_data).join("Root");
+
+    // Velopack writes the package nuspec to current/sq.version
+    if let Ok(nuspec) = fs::read_to_string(root_dir.join("current").join("sq.version")) {
+        let start = nuspec.find("<version>").map(|i| i + "<version>".len());
+        let end = nuspec.find("</version>");
+        if let (Some(start), Some(end)) = (start, end) {
+            return Some(nuspec[start..end].trim().to_string());
+        }
+    }
+
+    // Older Squirrel installs keep one app-x.y.z directory per version
+    fs::read_dir(&root_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .strip_prefix("app-")
+                .map(|v| v.to_string())
+        })
+        .max_by_key(|v| compat::parse_version(v))
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_root_version() -> Option<String> {
+    if is_snap_install() {
+        let snap_yaml = fs::read_to_string(format!("/snap/{}/current/meta/snap.yaml", SNAP_NAME)).ok()?;
+        return snap_yaml
+            .lines()
+            .find_map(|l| l.strip_prefix("version:"))
+            .map(|v| v.trim().trim_matches(|c| c == '\'' || c == '"').to_string());
+    }
+
+    // AppImages are often downloaded as Root-<version>.AppImage
+    let exe = get_root_exe_path();
+    let stem = exe.file_stem()?.to_string_lossy().to_string();
+    let version = stem.strip_prefix("Root-")?;
+    compat::parse_version(version).map(|_| version.to_string())
+}
+
+/// Whether Root is installed as a Snap package.
+#[cfg(target_os = "windows")]
+pub fn is_snap_install() -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_snap_install() -> bool {
+    let root_exe = get_root_exe_path();
+    root_exe.starts_with("/snap/")
+        || root_exe.file_name().is_some_and(|n| n == SNAP_NAME)
+        || get_profile_dir().starts_with(
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join("snap"),
+        )
+}
+
+/// How Root is packaged on this machine, which decides how it must be launched.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RootLayout {
+    AppImage,
+    /// An AppImage unpacked with `--appimage-extract`; its binary needs the bundled
+    /// libraries on `LD_LIBRARY_PATH`.
+    ExtractedAppImage,
+    NativeBinary,
+    Flatpak,
+    Snap,
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_root_layout() -> RootLayout {
+    RootLayout::NativeBinary
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_root_layout() -> RootLayout {
+    let exe = get_root_exe_path();
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    if is_snap_install() {
+        RootLayout::Snap
+    } else if exe.starts_with("/var/lib/flatpak")
+        || exe.starts_with(home.join(".local/share/flatpak"))
+        || get_profile_dir().starts_with(home.join(".var/app"))
+    {
+        RootLayout::Flatpak
+    } else if extracted_appimage_root().is_some() {
+        RootLayout::ExtractedAppImage
+    } else if exe.extension().is_some_and(|e| e.eq_ignore_ascii_case("AppImage")) {
+        RootLayout::AppImage
+    } else {
+        RootLayout::NativeBinary
+    }
+}
+
+/// How Root updates itself, which decides whether updates undo the HTML patches.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UpdateMechanism {
+    /// Squirrel or Velopack: `Update.exe` swaps in a fresh copy of the app.
+    Squirrel,
+    /// An NSIS installer reinstalling into the same directory.
+    Nsis,
+    /// A self-updating AppImage, replaced as a whole.
+    AppImage,
+    /// Updated by the package manager; the profile is kept.
+    Flatpak,
+    Snap,
+    Unknown,
+}
+
+impl UpdateMechanism {
+    /// Whether an update replaces the HTML files uprooted patches.
+    pub fn overwrites_html(self) -> bool {
+        matches!(self, UpdateMechanism::Squirrel | UpdateMechanism::AppImage)
+    }
+
+    /// Name shown to users in warnings.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            UpdateMechanism::Squirrel => "its built-in updater (Update.exe)",
+            UpdateMechanism::Nsis => "its installer",
+            UpdateMechanism::AppImage => "AppImage self-update",
+            UpdateMechanism::Flatpak => "Flatpak",
+            UpdateMechanism::Snap => "Snap",
+            UpdateMechanism::Unknown => "an unknown mechanism",
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_update_mechanism() -> UpdateMechanism {
+    let exe = get_root_exe_path();
+    let Some(exe_dir) = exe.parent() else {
+        return UpdateMechanism::Unknown;
+    };
+    // Velopack keeps Update.exe one level above `current`, classic Squirrel next to the exe.
+    let has_update_exe = exe_dir.join("Update.exe").exists()
+        || exe_dir.parent().is_some_and(|p| p.join("Update.exe").exists());
+    if has_update_exe {
+        UpdateMechanism::Squirrel
+    } else if exe_dir.join("Uninstall Root.exe").exists() {
+        UpdateMechanism::Nsis
+    } else {
+        UpdateMechanism::Unknown
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_update_mechanism() -> UpdateMechanism {
+    match detect_root_layout() {
+        RootLayout::Snap => UpdateMechanism::Snap,
+        RootLayout::Flatpak => UpdateMechanism::Flatpak,
+        RootLayout::AppImage => UpdateMechanism::AppImage,
+        _ if std::env::var_os("APPIMAGE").is_some() => UpdateMechanism::AppImage,
+        _ => UpdateMechanism::Unknown,
+    }
+}
+
+/// The `squashfs-root` directory of an extracted Root AppImage: either next to the
+/// detected AppImage, or the one the detected binary already lives in.
+#[cfg(target_os = "linux")]
+pub fn extracted_appimage_root() -> Option<PathBuf> {
+    let exe = get_root_exe_path();
+    let root = if exe.extension().is_some_and(|e| e.eq_ignore_ascii_case("AppImage")) {
+        exe.parent()?.join("squashfs-root")
+    } else {
+        exe.ancestors().find(|a| a.ends_with("squashfs-root"))?.to_path_buf()
+    };
+    root.join("usr/bin/Root").exists().then_some(root)
+}
+
+/// Detect whether the installer is running inside Windows Subsystem for Linux.
+#[cfg(target_os = "windows")]
+pub fn is_running_in_wsl() -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_running_in_wsl() -> bool {
+    if std::env::var("WSL_DISTRO_NAME").is_ok() {
+        return true;
+    }
+    fs::read_to_string("/proc/version")
+        .map(|v| v.to_lowercase().contains("microsoft"))
+        .unwrap_or(false)
+}
+
+pub const WSL_WARNING: &str = "The installer is running inside WSL. Root runs on the Windows host, \
+so run the Windows installer from Windows instead of from WSL.";
+
 pub fn find_target_html_files() -> Vec<PathBuf> {
     let profile = get_profile_dir();
     let mut targets = Vec::new();
@@ -78,26 +335,174 @@ pub fn find_target_html_files() -> Vec<PathBuf> {
     }
 
     // RootApps/*/index.html
-    let root_apps_dir = profile.join("RootApps");
-    if root_apps_dir.exists() {
-        if let Ok(entries) = fs::read_dir(&root_apps_dir) {
-            for entry in entries.flatten() {
-                if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-                    let app_index = entry.path().join("index.html");
-                    if app_index.exists() {
-                        targets.push(app_index);
-                    }
-                }
-            }
+    targets.extend(find_root_app_html_files(&profile.join("RootApps")));
+
+    // Files added by hand with `patch_file`
+    for custom in settings::load_settings().custom_targets {
+        let path = PathBuf::from(custom);
+        if path.exists() && !targets.contains(&path) {
+            targets.push(path);
         }
     }
 
     targets
 }
 
+fn is_dir_entry(entry: &fs::DirEntry) -> bool {
+    entry.file_type().map(|t| t.is_dir()).unwrap_or(false)
+}
+
+fn sort_by_path_string(paths: &mut [PathBuf]) {
+    paths.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
+}
+
+/// `<dir>/*/index.html`, sorted. Scanned in parallel since some users have dozens of
+/// RootApps and every detect, install and repair goes through here.
+fn find_root_app_html_files(dir: &Path) -> Vec<PathBuf> {
+    use rayon::prelude::*;
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut found: Vec<PathBuf> = entries
+        .flatten()
+        .par_bridge()
+        .filter(is_dir_entry)
+        .map(|entry| entry.path().join("index.html"))
+        .filter(|index| index.exists())
+        .collect();
+    sort_by_path_string(&mut found);
+    found
+}
+
+/// Per-file metadata for every target HTML file: size, mtime, patch state and backup.
+pub fn list_target_files() -> Vec<HtmlFileInfo> {
+    find_target_html_files()
+        .iter()
+        .map(|file| {
+            let metadata = fs::metadata(file).ok();
+            let modified = metadata
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .map(format_system_time)
+                .unwrap_or_default();
+            let content = read_html(file).unwrap_or_default();
+
+            HtmlFileInfo {
+                path: file.to_string_lossy().to_string(),
+                size_bytes: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                modified,
+                is_patched: patcher::is_patched(&content),
+                injection_version: patcher::injection_version(&content),
+                backup_exists: patcher::latest_backup_for(file).is_some(),
+            }
+        })
+        .collect()
+}
+
+fn format_system_time(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+}
+
+/// Text of the first `<title>` in `content`, found by a line scan rather than a parse.
+fn html_title(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let lower = line.to_ascii_lowercase();
+        let open = lower.find("<title")?;
+        let start = open + lower[open..].find('>')? + 1;
+        let end = start + lower[start..].find("</title")?;
+        let title = line[start..end].trim();
+        (!title.is_empty()).then(|| title.to_string())
+    })
+}
+
+/// One app in Root's `RootApps/apps.json`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RootAppManifestEntry {
+    /// The app's folder under `RootApps`, usually a UUID.
+    #[serde(alias = "dir", alias = "folder")]
+    pub id: String,
+    pub name: String,
+    #[serde(default, alias = "icon_url")]
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// `apps.json` is either a bare list or an object with an `apps` list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RootAppsManifest {
+    List(Vec<RootAppManifestEntry>),
+    Wrapped { apps: Vec<RootAppManifestEntry> },
+}
+
+/// Root's app list from `RootApps/apps.json`, if Root ships one and it parses.
+pub fn load_root_apps_manifest() -> Option<Vec<RootAppManifestEntry>> {
+    let path = get_profile_dir().join("RootApps").join("apps.json");
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(RootAppsManifest::List(apps) | RootAppsManifest::Wrapped { apps }) => Some(apps),
+        Err(e) => {
+            tracing::warn!("Ignoring {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn root_app_info(index_html: &Path, known_name: Option<&str>) -> RootAppInfo {
+    let app_dir = index_html.parent().unwrap_or(index_html);
+    let content = read_html(index_html).unwrap_or_default();
+    let app_name = known_name
+        .map(str::to_string)
+        .or_else(|| html_title(&content))
+        .unwrap_or_else(|| {
+            app_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+    RootAppInfo {
+        app_dir: app_dir.to_string_lossy().to_string(),
+        index_html: index_html.to_string_lossy().to_string(),
+        app_name,
+        is_patched: patcher::is_patched(&content),
+        injection_version: patcher::injection_version(&content),
+    }
+}
+
+/// Root's main UI and every RootApp, with a readable name and patch status each.
+pub fn list_root_apps() -> Vec<RootAppInfo> {
+    let profile = get_profile_dir();
+    let mut apps = Vec::new();
+    let webrtc_index = profile.join("WebRtcBundle").join("index.html");
+    if webrtc_index.exists() {
+        apps.push(root_app_info(&webrtc_index, Some("Root Main UI")));
+    }
+    let manifest = load_root_apps_manifest().unwrap_or_default();
+    apps.extend(
+        find_root_app_html_files(&profile.join("RootApps"))
+            .iter()
+            .map(|index| {
+                let dir_name = index.parent().and_then(Path::file_name);
+                let manifest_name = manifest
+                    .iter()
+                    .find(|entry| dir_name.is_some_and(|d| d == entry.id.as_str()))
+                    .map(|entry| entry.name.as_str());
+                root_app_info(index, manifest_name)
+            }),
+    );
+    apps
+}
+
+fn read_html(path: &Path) -> Option<String> {
+    encoding::detect_and_read_html(path).ok().map(|(content, _)| content)
+}
+
 pub fn check_is_installed(html_files: &[PathBuf]) -> bool {
     for file in html_files {
-        if let Ok(content) = fs::read_to_string(file) {
+        if let Some(content) = read_html(file) {
             if patcher::is_patched(&content) {
                 return true;
             }
@@ -110,11 +515,55 @@ pub fn detect() -> DetectionResult {
     let root_exe = get_root_exe_path();
     let profile = get_profile_dir();
     let html_files = find_target_html_files();
-    let is_installed = check_is_installed(&html_files);
+    let _span = crate::logging::timed(tracing::info_span!("detect", file_count = html_files.len()));
     let hook_status = hook::check_hook_status();
+    let is_installed = if hook_status.html_patching_disabled {
+        hook_status.env_ok
+    } else {
+        check_is_installed(&html_files)
+    };
+
+    let mut warnings = Vec::new();
+    let in_wsl = is_running_in_wsl();
+    if in_wsl {
+        warnings.push(WSL_WARNING.to_string());
+    }
+    if let Some(sign) = detect_root_builtin_theming() {
+        warnings.push(format!(
+            "Root's built-in theming was detected ({}). It may override uprooted themes; \
+             turn on disableRootBuiltinTheming in settings if theme colors don't apply.",
+            sign
+        ));
+    }
+    let root_version = get_root_version();
+    let compat_warning = compat::check_root_version(root_version.as_deref());
+    let running_with_hook = match hook::detect_launch_method() {
+        hook::LaunchMethod::Wrapper => Some(true),
+        hook::LaunchMethod::Direct => Some(false),
+        hook::LaunchMethod::Unknown => None,
+    };
+    if is_installed && running_with_hook == Some(false) {
+        warnings.push(format!(
+            "Root is running without uprooted's environment variables, so mods are not active. {}",
+            if cfg!(target_os = "windows") {
+                "Restart Root to pick them up."
+            } else {
+                "Restart Root through the \"Root (Uprooted)\" launcher or log out and back in."
+            }
+        ));
+    }
+
+    let update_mechanism = detect_update_mechanism();
+    if is_installed && update_mechanism.overwrites_html() {
+        warnings.push(format!(
+            "Root updates itself through {}, which replaces the patched HTML files. \
+             Keep the startup check on so uprooted is repaired after each Root update.",
+            update_mechanism.display_name()
+        ));
+    }
 
     DetectionResult {
-        root_found: root_exe.exists(),
+        root_found: root_exe.exists() && !in_wsl,
         root_path: root_exe.to_string_lossy().to_string(),
         profile_dir: profile.to_string_lossy().to_string(),
         html_files: html_files
@@ -122,6 +571,179 @@ pub fn detect() -> DetectionResult {
             .map(|p| p.to_string_lossy().to_string())
             .collect(),
         is_installed,
+        is_snap: is_snap_install(),
         hook_status,
+        warnings,
+        root_version,
+        compat_warning,
+        running_with_hook,
+        root_layout: detect_root_layout(),
+        update_mechanism,
+        apps_manifest_found: load_root_apps_manifest().is_some(),
+    }
+}
+
+/// Look for Root's own theme infrastructure in the target HTML files, which applies
+/// after page load and can override uprooted's variables. Returns what was found.
+pub fn detect_root_builtin_theming() -> Option<String> {
+    find_target_html_files().into_iter().find_map(|path| {
+        let content = patcher::strip_injection(&read_html(&path)?);
+        let lower = content.to_lowercase();
+        if lower.contains("root-theme.js") {
+            return Some(format!("root-theme.js in {}", path.display()));
+        }
+        let html_tag = lower
+            .find("<html")
+            .and_then(|start| lower[start..].find('>').map(|end| &lower[start..start + end]))?;
+        html_tag
+            .contains("data-theme")
+            .then(|| format!("data-theme on <html> in {}", path.display()))
+    })
+}
+
+#[derive(Serialize, Clone)]
+pub struct IntegrityEnforcementInfo {
+    pub detected: bool,
+    /// Hash manifest found next to the HTML files.
+    pub manifest_path: String,
+}
+
+/// Hash manifests that Root builds with integrity checking ship next to their HTML.
+const HASH_MANIFEST_SUFFIXES: &[&str] = &[".sri-manifest.json", ".hashes.json"];
+
+/// Last integrity check, keyed by the Root executable's modification time so it is
+/// redone after Root updates.
+type IntegrityCheck = (Option<SystemTime>, Option<IntegrityEnforcementInfo>);
+static INTEGRITY_CHECK: Mutex<Option<IntegrityCheck>> = Mutex::new(None);
+
+/// Whether Root appears to verify its HTML files against known hashes, in which
+/// case patching them would stop Root from loading them.
+pub fn detect_html_integrity_enforcement() -> Option<IntegrityEnforcementInfo> {
+    let exe_modified = fs::metadata(get_root_exe_path())
+        .and_then(|m| m.modified())
+        .ok();
+    let mut cached = INTEGRITY_CHECK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((modified, info)) = cached.as_ref() {
+        if *modified == exe_modified {
+            return info.clone();
+        }
+    }
+    let info = find_hash_manifest();
+    *cached = Some((exe_modified, info.clone()));
+    info
+}
+
+fn find_hash_manifest() -> Option<IntegrityEnforcementInfo> {
+    let profile = get_profile_dir();
+    let mut dirs: Vec<PathBuf> = find_target_html_files()
+        .iter()
+        .filter_map(|f| f.parent().map(Path::to_path_buf))
+        .collect();
+    dirs.push(profile);
+    dirs.dedup();
+
+    dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .find(|entry| {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            HASH_MANIFEST_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        })
+        .map(|entry| IntegrityEnforcementInfo {
+            detected: true,
+            manifest_path: entry.path().to_string_lossy().to_string(),
+        })
+}
+
+/// Last `detect()` result, shared through Tauri's managed state so repeated
+/// page loads don't rescan the profile directory.
+#[derive(Default)]
+pub struct DetectionCache(Mutex<Option<(DetectionResult, Instant)>>);
+
+impl DetectionCache {
+    /// Return the cached result if it is younger than `ttl`, otherwise rescan.
+    pub fn get_or_detect(&self, ttl: Duration) -> DetectionResult {
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((result, at)) = cached.as_ref() {
+            if at.elapsed() < ttl {
+                return result.clone();
+            }
+        }
+        let result = detect();
+        *cached = Some((result.clone(), Instant::now()));
+        result
+    }
+
+    pub fn invalidate(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(tag = "state", content = "version")]
+pub enum InstallState {
+    NotInstalled,
+    InstalledCurrentVersion,
+    InstalledOlderVersion(String),
+    InstalledNewerVersion(String),
+}
+
+/// Installer version of the existing install, from the receipt or the deploy manifest.
+/// The HTML version marker holds the preload.js version, so it only tells us whether
+/// something is installed; installs with neither file report `"unknown"`.
+fn installed_version() -> Option<String> {
+    if let Some(receipt) = receipt::read_receipt() {
+        return Some(receipt.uprooted_version);
+    }
+    if let Some(version) = integrity::deployed_version() {
+        return Some(version);
+    }
+    let patched = find_target_html_files()
+        .iter()
+        .filter_map(|file| read_html(file))
+        .any(|content| patcher::is_patched(&content));
+    patched.then(|| "unknown".to_string())
+}
+
+pub fn detect_install_state() -> InstallState {
+    let installed = match installed_version() {
+        Some(v) => v,
+        None => return InstallState::NotInstalled,
+    };
+    let current = semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("crate version is semver");
+    match semver::Version::parse(&installed) {
+        Ok(v) if v == current => InstallState::InstalledCurrentVersion,
+        Ok(v) if v > current => InstallState::InstalledNewerVersion(installed),
+        _ => InstallState::InstalledOlderVersion(installed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_app_scan_is_sorted_and_skips_apps_without_index() {
+        let dir = tempfile::tempdir().unwrap();
+        for app in ["zeta", "alpha", "mid", "empty"] {
+            fs::create_dir(dir.path().join(app)).unwrap();
+            if app != "empty" {
+                fs::write(dir.path().join(app).join("index.html"), "<html></html>").unwrap();
+            }
+        }
+        fs::write(dir.path().join("stray.html"), "").unwrap();
+
+        let found = find_root_app_html_files(dir.path());
+        let apps: Vec<String> = found
+            .iter()
+            .map(|p| p.parent().unwrap().file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(apps, ["alpha", "mid", "zeta"]);
+    }
+
+    #[test]
+    fn root_app_scan_of_missing_dir_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(find_root_app_html_files(&dir.path().join("RootApps")).is_empty());
     }
 }
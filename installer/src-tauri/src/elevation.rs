@@ -0,0 +1,187 @@
+//! UAC elevation for system-wide installs. Only the machine-wide env vars need an
+//! administrator, so that is all the elevated child does: the installer relaunches itself
+//! with `--elevated <request path> <result path>`, the child applies the
+//! [`ElevatedRequest`] headlessly and writes its outcome as JSON, and the parent reads it
+//! back once the child exits. Deploying and patching stay in the parent, because the
+//! elevated child may run as another account with its own profile, settings and HKCU.
+
+#[cfg(target_os = "windows")]
+use crate::error::UprootedError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Passed to the relaunched installer, followed by the request and result paths.
+pub const ELEVATED_FLAG: &str = "--elevated";
+
+/// Work handed to the elevated child. The values are computed by the parent, since
+/// paths such as `CORECLR_PROFILER_PATH` depend on the calling user's profile.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "action", content = "vars", rename_all = "snake_case")]
+pub enum ElevatedRequest {
+    /// Write these machine-wide env vars and delete the other hook env vars.
+    SetEnvVars(Vec<(String, String)>),
+    /// Delete every hook env var from the machine-wide environment.
+    RemoveEnvVars,
+}
+
+/// Temp files for the request to and the result from the elevated child.
+#[cfg(target_os = "windows")]
+fn elevated_paths() -> (PathBuf, PathBuf) {
+    let base = format!("uprooted-elevated-{}", std::process::id());
+    let dir = std::env::temp_dir();
+    (dir.join(format!("{}-request.json", base)), dir.join(format!("{}-result.json", base)))
+}
+
+/// The request and result paths if this process was started with
+/// `--elevated <request> <result>`.
+pub fn elevated_args() -> Option<(PathBuf, PathBuf)> {
+    let mut args = std::env::args().skip_while(|a| a != ELEVATED_FLAG);
+    args.next()?;
+    Some((PathBuf::from(args.next()?), PathBuf::from(args.next()?)))
+}
+
+/// Run `request` in an elevated copy of the installer and return its outcome.
+#[cfg(target_os = "windows")]
+pub fn run_elevated(request: &ElevatedRequest) -> Result<(), UprootedError> {
+    let (request_path, result_path) = elevated_paths();
+    let json = serde_json::to_string(request)
+        .map_err(|e| UprootedError::Io(format!("Failed to serialize the request: {}", e)))?;
+    std::fs::write(&request_path, json).map_err(|e| {
+        UprootedError::Io(format!("Failed to write {}: {}", request_path.display(), e))
+    })?;
+
+    let elevated = request_elevation(&request_path, &result_path);
+    let _ = std::fs::remove_file(&request_path);
+    elevated?;
+
+    let outcome = std::fs::read_to_string(&result_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            serde_json::from_str::<Result<(), String>>(&json).map_err(|e| e.to_string())
+        });
+    let _ = std::fs::remove_file(&result_path);
+    match outcome {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(UprootedError::Registry(e)),
+        Err(e) => Err(UprootedError::Io(format!(
+            "The elevated installer did not report a result: {}",
+            e
+        ))),
+    }
+}
+
+/// Whether the current process token is elevated (running as administrator).
+#[cfg(target_os = "windows")]
+pub fn is_elevated() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut returned = 0u32;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut std::ffi::c_void,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+        CloseHandle(token);
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Relaunch the installer through the UAC `runas` verb and wait for it to exit.
+/// Declining the prompt returns `ElevationCancelled`.
+#[cfg(target_os = "windows")]
+fn request_elevation(
+    request_path: &std::path::Path,
+    result_path: &std::path::Path,
+) -> Result<(), UprootedError> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_CANCELLED};
+    use windows_sys::Win32::System::Threading::{WaitForSingleObject, INFINITE};
+    use windows_sys::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+    let wide = |s: &OsStr| s.encode_wide().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let exe = std::env::current_exe()
+        .map_err(|e| UprootedError::Io(format!("Failed to locate the installer: {}", e)))?;
+    let verb = wide(OsStr::new("runas"));
+    let file = wide(exe.as_os_str());
+    let params = wide(OsStr::new(&format!(
+        "{} \"{}\" \"{}\"",
+        ELEVATED_FLAG,
+        request_path.display(),
+        result_path.display()
+    )));
+
+    unsafe {
+        let mut info: SHELLEXECUTEINFOW = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+        info.fMask = SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC;
+        info.lpVerb = verb.as_ptr();
+        info.lpFile = file.as_ptr();
+        info.lpParameters = params.as_ptr();
+        info.nShow = SW_HIDE;
+
+        if ShellExecuteExW(&mut info) == 0 {
+            let code = GetLastError();
+            if code == ERROR_CANCELLED {
+                return Err(UprootedError::ElevationCancelled);
+            }
+            return Err(UprootedError::Io(format!(
+                "Failed to start the elevated installer (error {})",
+                code
+            )));
+        }
+        if !info.hProcess.is_null() {
+            WaitForSingleObject(info.hProcess, INFINITE);
+            CloseHandle(info.hProcess);
+        }
+    }
+    Ok(())
+}
+
+/// Child side of [`run_elevated`]: apply the request at `request_path` and write the
+/// outcome to `result_path`.
+pub fn handle_elevated_request(request_path: &std::path::Path, result_path: &std::path::Path) {
+    let outcome = std::fs::read_to_string(request_path)
+        .map_err(|e| format!("Failed to read {}: {}", request_path.display(), e))
+        .and_then(|json| {
+            serde_json::from_str::<ElevatedRequest>(&json)
+                .map_err(|e| format!("Invalid elevated request: {}", e))
+        })
+        .and_then(|request| apply(&request));
+    match &outcome {
+        Ok(()) => tracing::info!("elevated request applied"),
+        Err(e) => tracing::error!("elevated request failed: {}", e),
+    }
+    let json = serde_json::to_string(&outcome).unwrap_or_else(|_| "{\"Err\":\"\"}".to_string());
+    if let Err(e) = std::fs::write(result_path, json) {
+        tracing::error!("Failed to write {}: {}", result_path.display(), e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply(request: &ElevatedRequest) -> Result<(), String> {
+    match request {
+        ElevatedRequest::SetEnvVars(vars) => crate::hook::set_system_env_vars(vars),
+        ElevatedRequest::RemoveEnvVars => crate::hook::remove_system_env_vars(),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn apply(_request: &ElevatedRequest) -> Result<(), String> {
+    Err("Elevated requests are only supported on Windows".to_string())
+}
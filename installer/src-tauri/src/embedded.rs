@@ -1,15 +1,44 @@
-/// Embedded binary artifacts for deployment.
-///
-/// These files are compiled into the installer binary via `include_bytes!()`.
-/// The build pipeline stages real builds into `installer/src-tauri/artifacts/`
-/// before `cargo tauri build`.
+//! Embedded binary artifacts for deployment.
+//!
+//! These files are compiled into the installer binary via `include_bytes!()`.
+//! The build pipeline stages real builds into `installer/src-tauri/artifacts/`
+//! before `cargo tauri build`; `build.rs` zstd-compresses each one into `OUT_DIR`,
+//! so every artifact here is compressed and must go through `decompress()`.
+//!
+//! The compressed bytes are plain `'static` slices; nothing is decompressed until
+//! an artifact is actually deployed or inspected.
+
+use crate::error::UprootedError;
 
 #[cfg(target_os = "windows")]
-pub const PROFILER: &[u8] = include_bytes!("../artifacts/uprooted_profiler.dll");
+pub static PROFILER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/uprooted_profiler.dll.zst"));
 #[cfg(target_os = "linux")]
-pub const PROFILER: &[u8] = include_bytes!("../artifacts/libuprooted_profiler.so");
+pub static PROFILER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/libuprooted_profiler.so.zst"));
+
+pub static HOOK_DLL: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/UprootedHook.dll.zst"));
+pub static HOOK_DEPS_JSON: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/UprootedHook.deps.json.zst"));
+pub static PRELOAD_JS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/uprooted-preload.js.zst"));
+pub static THEME_CSS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/uprooted.css.zst"));
+
+/// Explains the deployed files to anyone who opens the uprooted directory. Small
+/// enough to embed as plain text.
+pub const README_TXT: &str = include_str!("../artifacts/README.txt");
+
+/// Decompress an embedded artifact. The data is produced by our own build script,
+/// so a decode failure means the installer binary itself is corrupt.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, UprootedError> {
+    zstd::decode_all(data).map_err(|e| {
+        UprootedError::Io(format!(
+            "An embedded file is corrupt ({}). Download the installer again.",
+            e
+        ))
+    })
+}
 
-pub const HOOK_DLL: &[u8] = include_bytes!("../artifacts/UprootedHook.dll");
-pub const HOOK_DEPS_JSON: &[u8] = include_bytes!("../artifacts/UprootedHook.deps.json");
-pub const PRELOAD_JS: &[u8] = include_bytes!("../artifacts/uprooted-preload.js");
-pub const THEME_CSS: &[u8] = include_bytes!("../artifacts/uprooted.css");
+/// Uncompressed size recorded in the zstd frame header, read without decompressing.
+pub fn content_size(data: &[u8]) -> Option<usize> {
+    zstd::zstd_safe::get_frame_content_size(data)
+        .ok()
+        .flatten()
+        .map(|n| n as usize)
+}
@@ -0,0 +1,10 @@
+//! ARM64 builds of the native profiler, selected at runtime by `hook::native_arch()`.
+//!
+//! Staged as `artifacts/uprooted_profiler_arm64.dll` / `artifacts/libuprooted_profiler_arm64.so`.
+//! If the pipeline didn't produce one, `build.rs` embeds an empty slice and the
+//! default profiler is deployed instead.
+
+#[cfg(target_os = "windows")]
+pub static PROFILER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/uprooted_profiler_arm64.dll.zst"));
+#[cfg(target_os = "linux")]
+pub static PROFILER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/libuprooted_profiler_arm64.so.zst"));
@@ -0,0 +1,35 @@
+/// Release notes shown in the installer UI.
+pub const CHANGELOG: &str = include_str!("../CHANGELOG.md");
+
+/// Extract the section for `version`, from its `## [x.y.z]` heading up to the next one.
+pub fn version_section(version: &str) -> Option<String> {
+    let heading = format!("## [{}]", version.trim_start_matches('v'));
+    let mut lines = CHANGELOG.lines().skip_while(|l| !l.starts_with(&heading));
+    let first = lines.next()?;
+
+    let mut section = vec![first];
+    section.extend(lines.take_while(|l| !l.starts_with("## [")));
+    Some(section.join("\n").trim_end().to_string())
+}
+
+/// Every section newer than `from` up to and including `to`, newest first, as in
+/// the file. Headings that aren't semver (e.g. `## [Unreleased]`) are skipped.
+pub fn get_changelog_range(from: &str, to: &str) -> String {
+    let parse = |v: &str| semver::Version::parse(v.trim().trim_start_matches('v')).ok();
+    let (Some(from), Some(to)) = (parse(from), parse(to)) else {
+        return String::new();
+    };
+
+    let mut sections = Vec::new();
+    let mut include = false;
+    for line in CHANGELOG.lines() {
+        if let Some(heading) = line.strip_prefix("## [") {
+            let version = heading.split(']').next().and_then(parse);
+            include = version.is_some_and(|v| v > from && v <= to);
+        }
+        if include {
+            sections.push(line);
+        }
+    }
+    sections.join("\n").trim_end().to_string()
+}
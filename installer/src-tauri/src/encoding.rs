@@ -0,0 +1,102 @@
+use crate::error::UprootedError;
+use std::fs;
+use std::path::Path;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// On-disk encoding of a target HTML file, kept so patched files are written back
+/// the way Root (or the user) saved them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmlEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// Read an HTML file as UTF-8, UTF-8 with BOM, or BOM-marked UTF-16.
+/// Anything else fails with `UnsupportedEncoding` naming the charset the file declares.
+pub fn detect_and_read_html(path: &Path) -> Result<(String, HtmlEncoding), UprootedError> {
+    let bytes =
+        fs::read(path).map_err(|e| UprootedError::ReadFailed(format!("{}: {}", path.display(), e)))?;
+
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        if let Ok(text) = std::str::from_utf8(rest) {
+            return Ok((text.to_string(), HtmlEncoding::Utf8Bom));
+        }
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        return decode_utf16(path, rest, encoding_rs::UTF_16LE, HtmlEncoding::Utf16Le);
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        return decode_utf16(path, rest, encoding_rs::UTF_16BE, HtmlEncoding::Utf16Be);
+    } else if let Ok(text) = String::from_utf8(bytes.clone()) {
+        return Ok((text, HtmlEncoding::Utf8));
+    }
+
+    Err(UprootedError::UnsupportedEncoding(format!(
+        "{} ({})",
+        path.display(),
+        declared_charset(&bytes).unwrap_or_else(|| "unknown".to_string())
+    )))
+}
+
+fn decode_utf16(
+    path: &Path,
+    data: &[u8],
+    encoding: &'static encoding_rs::Encoding,
+    kind: HtmlEncoding,
+) -> Result<(String, HtmlEncoding), UprootedError> {
+    let (text, had_errors) = encoding.decode_without_bom_handling(data);
+    if had_errors {
+        return Err(UprootedError::UnsupportedEncoding(format!(
+            "{} ({}, malformed)",
+            path.display(),
+            encoding.name()
+        )));
+    }
+    Ok((text.into_owned(), kind))
+}
+
+/// Charset named by a `<meta charset>` or `http-equiv` content type, canonicalized
+/// to its WHATWG name. Only the first 1 KB is scanned, as browsers do.
+fn declared_charset(bytes: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_lowercase();
+    let start = head.find("charset=")? + "charset=".len();
+    let label: String = head[start..]
+        .trim_start_matches(['"', '\''])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+        .collect();
+    if label.is_empty() {
+        return None;
+    }
+    Some(
+        encoding_rs::Encoding::for_label(label.as_bytes())
+            .map(|e| e.name().to_string())
+            .unwrap_or(label),
+    )
+}
+
+/// Encode `content` back into `encoding`, including its BOM.
+pub fn encode_html(content: &str, encoding: HtmlEncoding) -> Vec<u8> {
+    match encoding {
+        HtmlEncoding::Utf8 => content.as_bytes().to_vec(),
+        HtmlEncoding::Utf8Bom => [UTF8_BOM, content.as_bytes()].concat(),
+        // encoding_rs only encodes to UTF-8 for UTF-16 labels, so do it by hand.
+        HtmlEncoding::Utf16Le => UTF16_LE_BOM
+            .iter()
+            .copied()
+            .chain(content.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        HtmlEncoding::Utf16Be => UTF16_BE_BOM
+            .iter()
+            .copied()
+            .chain(content.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+    }
+}
+
+pub fn write_html(path: &Path, content: &str, encoding: HtmlEncoding) -> std::io::Result<()> {
+    fs::write(path, encode_html(content, encoding))
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+
+// Stable error codes. Bug reports, the changelog and the help pages refer to these,
+// so never renumber or reuse one.
+pub const E_IO: u32 = 1001;
+pub const E_REGISTRY: u32 = 1002;
+pub const E_READ_FAILED: u32 = 1003;
+pub const E_CREATE_DIR_FAILED: u32 = 1004;
+pub const E_PATCH_FAILED: u32 = 2001;
+pub const E_NO_TARGET_FILES: u32 = 2002;
+pub const E_UNSUPPORTED_ENCODING: u32 = 2003;
+pub const E_DEPLOYMENT_FAILED: u32 = 3001;
+pub const E_SIGNATURE_INVALID: u32 = 3002;
+pub const E_PATH_TRAVERSAL: u32 = 4001;
+pub const E_PREREQUISITE_FAILED: u32 = 5001;
+pub const E_DISABLED: u32 = 5002;
+pub const E_READ_ONLY: u32 = 5003;
+pub const E_ELEVATION_CANCELLED: u32 = 5004;
+pub const E_OPERATION_RUNNING: u32 = 5005;
+
+#[derive(Debug)]
+pub enum UprootedError {
+    Io(String),
+    /// A deployed binary failed Authenticode verification.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    SignatureInvalid(String),
+    /// A path derived from untrusted input resolved outside its base directory.
+    PathTraversal(String),
+    /// A file could not be read, usually because Root holds it open.
+    ReadFailed(String),
+    /// A directory could not be created.
+    CreateDirFailed(String),
+    /// The profile has no HTML files to patch yet.
+    NoTargetFiles,
+    /// Reading or writing `HKCU\Environment` failed.
+    Registry(String),
+    /// An HTML file could not be backed up, written or restored.
+    PatchFailed(String),
+    /// A hook file or the deploy manifest could not be written to the uprooted directory.
+    DeploymentFailed(String),
+    /// A blocking pre-install check failed.
+    PrerequisiteFailed(String),
+    /// An HTML file is neither UTF-8 nor BOM-marked UTF-16.
+    UnsupportedEncoding(String),
+    /// `enabled` is off in settings, so mutating operations are skipped.
+    Disabled,
+    /// The install or profile directory can't be written by the current user.
+    ReadOnly(String),
+    /// The UAC prompt for a system-wide install was declined.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    ElevationCancelled,
+    /// Another install, repair or uninstall is still running; holds its label.
+    OperationRunning(String),
+}
+
+impl UprootedError {
+    /// Stable numeric code for this error, see the `E_*` constants.
+    pub fn code(&self) -> u32 {
+        match self {
+            UprootedError::Io(_) => E_IO,
+            UprootedError::SignatureInvalid(_) => E_SIGNATURE_INVALID,
+            UprootedError::PathTraversal(_) => E_PATH_TRAVERSAL,
+            UprootedError::ReadFailed(_) => E_READ_FAILED,
+            UprootedError::CreateDirFailed(_) => E_CREATE_DIR_FAILED,
+            UprootedError::NoTargetFiles => E_NO_TARGET_FILES,
+            UprootedError::Registry(_) => E_REGISTRY,
+            UprootedError::PatchFailed(_) => E_PATCH_FAILED,
+            UprootedError::DeploymentFailed(_) => E_DEPLOYMENT_FAILED,
+            UprootedError::PrerequisiteFailed(_) => E_PREREQUISITE_FAILED,
+            UprootedError::UnsupportedEncoding(_) => E_UNSUPPORTED_ENCODING,
+            UprootedError::Disabled => E_DISABLED,
+            UprootedError::ReadOnly(_) => E_READ_ONLY,
+            UprootedError::ElevationCancelled => E_ELEVATION_CANCELLED,
+            UprootedError::OperationRunning(_) => E_OPERATION_RUNNING,
+        }
+    }
+}
+
+/// Env var changes go through the registry on Windows and config files on Linux.
+pub fn env_var_error(msg: String) -> UprootedError {
+    if cfg!(target_os = "windows") {
+        UprootedError::Registry(msg)
+    } else {
+        UprootedError::Io(msg)
+    }
+}
+
+impl fmt::Display for UprootedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UprootedError::Io(msg) => write!(f, "{}", msg),
+            UprootedError::SignatureInvalid(path) => {
+                write!(f, "Signature verification failed for {}", path)
+            }
+            UprootedError::PathTraversal(path) => {
+                write!(f, "Path escapes its allowed directory: {}", path)
+            }
+            UprootedError::ReadFailed(msg) => write!(f, "Failed to read {}", msg),
+            UprootedError::CreateDirFailed(msg) => write!(f, "Failed to create directory {}", msg),
+            UprootedError::NoTargetFiles => {
+                write!(f, "No target HTML files found in profile directory.")
+            }
+            UprootedError::Registry(msg)
+            | UprootedError::PatchFailed(msg)
+            | UprootedError::DeploymentFailed(msg) => write!(f, "{}", msg),
+            UprootedError::PrerequisiteFailed(msg) => write!(f, "Prerequisite failed: {}", msg),
+            UprootedError::UnsupportedEncoding(msg) => write!(f, "Unsupported file encoding: {}", msg),
+            UprootedError::Disabled => write!(f, "Uprooted is disabled in settings"),
+            UprootedError::ReadOnly(path) => write!(f, "{} is not writable by this user", path),
+            UprootedError::ElevationCancelled => {
+                write!(
+                    f,
+                    "Administrator access was declined; the system environment was not changed."
+                )
+            }
+            UprootedError::OperationRunning(label) => {
+                write!(f, "Another operation is already running ({})", label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UprootedError {}
+
+/// A user-actionable next step for errors with a known fix.
+pub fn suggest_recovery(error: &UprootedError) -> Option<String> {
+    let hint = match error {
+        UprootedError::ReadFailed(_) => "Ensure Root is closed and try again.",
+        UprootedError::CreateDirFailed(_) => {
+            if cfg!(target_os = "windows") {
+                "Check that you have write permission to LocalAppData."
+            } else {
+                "Check that you have write permission to ~/.local/share."
+            }
+        }
+        UprootedError::ReadOnly(_) => {
+            "Run the installer as the user who installed uprooted, or fix the folder's permissions."
+        }
+        UprootedError::ElevationCancelled => {
+            "Accept the administrator prompt, or set the install scope back to the current user."
+        }
+        UprootedError::OperationRunning(_) => "Wait for it to finish, then try again.",
+        UprootedError::DeploymentFailed(_) => {
+            "Close Root, which keeps the hook files open while it runs, and try again."
+        }
+        UprootedError::NoTargetFiles => "Run Root at least once to initialize the profile.",
+        UprootedError::UnsupportedEncoding(_) => {
+            "Re-save the file as UTF-8, or reinstall Root to restore the original."
+        }
+        _ => return None,
+    };
+    Some(hint.to_string())
+}
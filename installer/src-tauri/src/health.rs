@@ -0,0 +1,146 @@
+use crate::detection;
+use crate::hook;
+use crate::patcher;
+use crate::prerequisites::CheckSeverity;
+use serde::Serialize;
+
+const MAX_SCORE: u8 = 100;
+const TAMPERED_FILE_PENALTY: u8 = 10;
+const ARCH_MISMATCH_PENALTY: u8 = 20;
+
+#[derive(Serialize)]
+pub struct HealthIssue {
+    pub description: String,
+    pub severity: CheckSeverity,
+    /// Tauri command that fixes the issue, for a one-click fix in the UI.
+    pub fix_command: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct HealthScore {
+    pub score: u8,
+    pub max_score: u8,
+    pub grade: char,
+    pub issues: Vec<HealthIssue>,
+}
+
+fn issue(description: impl Into<String>, severity: CheckSeverity, fix: &str) -> HealthIssue {
+    HealthIssue {
+        description: description.into(),
+        severity,
+        fix_command: Some(fix.to_string()),
+    }
+}
+
+fn grade(score: u8) -> char {
+    match score {
+        90.. => 'A',
+        80..=89 => 'B',
+        70..=79 => 'C',
+        60..=69 => 'D',
+        _ => 'F',
+    }
+}
+
+/// Score the install out of 100: profiler (20), hook DLL (20), env vars (30),
+/// HTML patched (20) and patched by this version (10), minus penalties for
+/// tampered files and a wrong-architecture profiler.
+pub fn get_health_score() -> HealthScore {
+    let status = hook::check_hook_status();
+    let files = detection::list_target_files();
+    let nothing_installed = !status.profiler_dll && !status.hook_dll && !status.env_ok;
+    let fix = if nothing_installed {
+        "install_uprooted"
+    } else {
+        "repair_uprooted"
+    };
+
+    let mut score: u8 = 0;
+    let mut issues = Vec::new();
+
+    if status.profiler_dll {
+        score += 20;
+    } else {
+        issues.push(issue("The profiler is not deployed.", CheckSeverity::Error, fix));
+    }
+    if status.hook_dll {
+        score += 20;
+    } else {
+        issues.push(issue("The hook DLL is not deployed.", CheckSeverity::Error, fix));
+    }
+    if status.env_ok {
+        score += 30;
+    } else if status.env_profiler_path && !status.env_profiler_path_valid {
+        issues.push(issue(
+            "CORECLR_PROFILER_PATH does not point at the deployed profiler.",
+            CheckSeverity::Error,
+            "repair_uprooted",
+        ));
+    } else {
+        issues.push(issue(
+            "The environment variables are not set.",
+            CheckSeverity::Error,
+            fix,
+        ));
+    }
+
+    let unpatched = files.iter().filter(|f| !f.is_patched).count();
+    let stale = files
+        .iter()
+        .filter(|f| f.is_patched && patcher::is_injection_outdated(f.injection_version.as_deref()))
+        .count();
+    if status.html_patching_disabled {
+        score += 30;
+    } else if files.is_empty() {
+        issues.push(HealthIssue {
+            description: "No HTML files were found to patch. Run Root once first.".to_string(),
+            severity: CheckSeverity::Warning,
+            fix_command: None,
+        });
+    } else {
+        if unpatched == 0 {
+            score += 20;
+        } else {
+            issues.push(issue(
+                format!("{} of {} HTML files are not patched.", unpatched, files.len()),
+                CheckSeverity::Error,
+                fix,
+            ));
+        }
+        if unpatched < files.len() && stale == 0 {
+            score += 10;
+        } else if stale > 0 {
+            issues.push(issue(
+                format!("{} HTML files were patched by another version.", stale),
+                CheckSeverity::Warning,
+                "repair_uprooted",
+            ));
+        }
+    }
+
+    if !status.tampered_files.is_empty() {
+        let penalty = TAMPERED_FILE_PENALTY
+            .saturating_mul(u8::try_from(status.tampered_files.len()).unwrap_or(u8::MAX));
+        score = score.saturating_sub(penalty);
+        issues.push(issue(
+            format!("Modified since deployment: {}", status.tampered_files.join(", ")),
+            CheckSeverity::Warning,
+            "repair_uprooted",
+        ));
+    }
+    if status.dll_arch_mismatch {
+        score = score.saturating_sub(ARCH_MISMATCH_PENALTY);
+        issues.push(issue(
+            "The deployed profiler was built for another CPU architecture.",
+            CheckSeverity::Error,
+            "repair_uprooted",
+        ));
+    }
+
+    HealthScore {
+        score,
+        max_score: MAX_SCORE,
+        grade: grade(score),
+        issues,
+    }
+}
@@ -1,5 +1,16 @@
+use crate::css;
 use crate::embedded;
+use crate::embedded_arm64;
+use crate::integrity;
+use crate::receipt;
+use crate::error::UprootedError;
+use crate::settings::{assert_enabled, load_settings, save_settings, DesktopFileConfig, HookMode};
+#[cfg(target_os = "windows")]
+use crate::elevation::{self, ElevatedRequest};
+#[cfg(target_os = "windows")]
+use crate::settings::InstallScope;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -9,6 +20,15 @@ use winreg::enums::*;
 use winreg::RegKey;
 
 const PROFILER_GUID: &str = "{D1A6F5A0-1234-4567-89AB-CDEF01234567}";
+/// Plain-text guide to the deployed files, written next to them.
+const README_FILE: &str = "README.txt";
+
+/// CLSID registered for the profiler: the `profiler_guid` setting, or `PROFILER_GUID`.
+fn profiler_guid() -> String {
+    load_settings()
+        .profiler_guid
+        .unwrap_or_else(|| PROFILER_GUID.to_string())
+}
 
 #[cfg(target_os = "windows")]
 const ENV_VARS: &[&str] = &[
@@ -29,28 +49,71 @@ pub struct HookStatus {
     pub env_enable_profiling: bool,
     pub env_profiler_guid: bool,
     pub env_profiler_path: bool,
+    /// CORECLR_PROFILER_PATH is set and points at the profiler in the uprooted directory
+    pub env_profiler_path_valid: bool,
     pub env_ready_to_run: bool,
+    /// DOTNET_STARTUP_HOOKS points at UprootedHook.dll
+    pub env_startup_hook: bool,
     /// True if all files are deployed
     pub files_ok: bool,
-    /// True if all env vars are set correctly
+    /// True if the env vars required by the configured hook mode are set
     pub env_ok: bool,
     /// True if env vars are active in the current process environment (Linux only).
     /// On Windows this always matches env_ok since registry changes apply immediately.
     pub env_vars_active: bool,
+    /// Deployed files whose hash no longer matches the deployment manifest
+    pub tampered_files: Vec<String>,
+    /// True if the deployed profiler was built for a different CPU architecture
+    pub dll_arch_mismatch: bool,
+    /// From the install receipt, if one was written.
+    pub installed_at: Option<String>,
+    pub installer_version: Option<String>,
+    pub last_repaired_at: Option<String>,
+    /// HTML patching is skipped on purpose (`HookMode::EnvOnlyNoHtml`); `files_ok`
+    /// and `env_ok` alone mean the install is complete.
+    pub html_patching_disabled: bool,
+    /// Each variable the configured hook mode needs, with its current value.
+    pub env_vars: Vec<EnvVarInfo>,
+    /// The global `enabled` setting; when off, install and repair do nothing.
+    pub settings_enabled: bool,
+    /// Size in bytes of each deployed artifact that exists.
+    pub file_sizes: HashMap<String, u64>,
+    /// A deployed binary is over 10% smaller than the embedded copy, e.g. after a
+    /// partial write or an AV scanner zeroing it out.
+    pub files_possibly_truncated: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct EnvVarInfo {
+    pub key: String,
+    pub intended_value: String,
+    pub current_value: Option<String>,
+    pub is_set_correctly: bool,
+    /// Where the value is configured (registry key or config file).
+    pub scope: String,
 }
 
 // ==================== Platform-specific: install directory ====================
 
+/// Where the hook files are deployed. `UPROOTED_DIR` overrides it; like
+/// `UPROOTED_PROFILE_DIR`, it is meant for development and CI only.
+pub fn get_uprooted_dir() -> PathBuf {
+    match std::env::var_os("UPROOTED_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => default_uprooted_dir(),
+    }
+}
+
 /// Returns `%LOCALAPPDATA%\Root\uprooted\` on Windows.
 #[cfg(target_os = "windows")]
-pub fn get_uprooted_dir() -> PathBuf {
+fn default_uprooted_dir() -> PathBuf {
     let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
     PathBuf::from(local_app_data).join("Root").join("uprooted")
 }
 
 /// Returns `~/.local/share/uprooted/` on Linux.
 #[cfg(target_os = "linux")]
-pub fn get_uprooted_dir() -> PathBuf {
+fn default_uprooted_dir() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_default();
     PathBuf::from(home).join(".local/share/uprooted")
 }
@@ -62,26 +125,322 @@ const PROFILER_FILENAME: &str = "uprooted_profiler.dll";
 #[cfg(target_os = "linux")]
 const PROFILER_FILENAME: &str = "libuprooted_profiler.so";
 
-// ==================== Deploy files ====================
-
-/// Deploy all embedded files to the install directory.
-pub fn deploy_files() -> Result<(), String> {
+/// Variables to set for `mode`, as (name, value) pairs. Anything else in `ENV_VARS`
+/// is removed so switching modes doesn't leave the other mode's vars behind.
+fn hook_env_vars(mode: HookMode) -> Vec<(&'static str, String)> {
     let dir = get_uprooted_dir();
-    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    match mode {
+        HookMode::Profiler | HookMode::EnvOnlyNoHtml => vec![
+            ("CORECLR_ENABLE_PROFILING", "1".to_string()),
+            ("CORECLR_PROFILER", profiler_guid()),
+            (
+                "CORECLR_PROFILER_PATH",
+                dir.join(PROFILER_FILENAME).to_string_lossy().to_string(),
+            ),
+            ("DOTNET_ReadyToRun", "0".to_string()),
+        ],
+        HookMode::StartupHookOnly => vec![(
+            "DOTNET_STARTUP_HOOKS",
+            dir.join("UprootedHook.dll").to_string_lossy().to_string(),
+        )],
+    }
+}
 
-    let files: &[(&str, &[u8])] = &[
-        (PROFILER_FILENAME, embedded::PROFILER),
+/// Whether a CORECLR_PROFILER_PATH value names the deployed profiler. Windows paths
+/// compare case-insensitively and with either slash.
+fn is_deployed_profiler_path(value: &str) -> bool {
+    let normalize = |p: &str| {
+        let p = p.trim().trim_matches('"');
+        if cfg!(target_os = "windows") {
+            p.replace('/', "\\").trim_end_matches('\\').to_lowercase()
+        } else {
+            p.trim_end_matches('/').to_string()
+        }
+    };
+    let expected = get_uprooted_dir().join(PROFILER_FILENAME);
+    !value.is_empty() && normalize(value) == normalize(&expected.to_string_lossy())
+}
+
+/// Whether the env vars required by `mode` are configured.
+fn env_vars_ok(mode: HookMode, enable: bool, guid: bool, path: bool, startup_hook: bool) -> bool {
+    match mode {
+        HookMode::Profiler | HookMode::EnvOnlyNoHtml => enable && guid && path,
+        HookMode::StartupHookOnly => startup_hook,
+    }
+}
+
+// ==================== Deploy files ====================
+
+/// Every embedded artifact paired with the filename it is deployed under.
+/// The bytes are zstd-compressed; see `embedded::decompress()`.
+pub fn embedded_artifacts() -> [(&'static str, &'static [u8]); 5] {
+    [
+        (PROFILER_FILENAME, profiler_bytes()),
         ("UprootedHook.dll", embedded::HOOK_DLL),
         ("UprootedHook.deps.json", embedded::HOOK_DEPS_JSON),
         ("uprooted-preload.js", embedded::PRELOAD_JS),
         ("uprooted.css", embedded::THEME_CSS),
+    ]
+}
+
+/// Uncompressed size of an embedded artifact, from its zstd header.
+pub fn artifact_size(name: &str) -> Option<usize> {
+    embedded_artifacts()
+        .iter()
+        .find(|(n, _)| *n == name)
+        .and_then(|(_, data)| embedded::content_size(data))
+}
+
+/// Profiler build matching the machine's native architecture, falling back to
+/// the default build when no ARM64 variant was embedded.
+fn profiler_bytes() -> &'static [u8] {
+    if native_arch() == "aarch64" && !embedded_arm64::PROFILER.is_empty() {
+        embedded_arm64::PROFILER
+    } else {
+        embedded::PROFILER
+    }
+}
+
+/// Native CPU architecture, in `std::env::consts::ARCH` naming. Unlike `ARCH`
+/// this sees through x64 emulation on ARM64 Windows.
+#[cfg(target_os = "windows")]
+pub fn native_arch() -> &'static str {
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+    let mut process_machine: u16 = 0;
+    let mut native_machine: u16 = 0;
+    let ok = unsafe {
+        IsWow64Process2(GetCurrentProcess(), &mut process_machine, &mut native_machine)
+    };
+    if ok == 0 {
+        return std::env::consts::ARCH;
+    }
+    match native_machine {
+        0xAA64 => "aarch64",
+        0x8664 => "x86_64",
+        0x014C => "x86",
+        _ => std::env::consts::ARCH,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn native_arch() -> &'static str {
+    std::env::consts::ARCH
+}
+
+/// Read the target architecture from a PE or ELF header.
+pub fn binary_arch(data: &[u8]) -> Option<&'static str> {
+    // ELF: e_machine is a u16 at offset 18, endianness given by EI_DATA
+    if data.starts_with(b"\x7fELF") && data.len() >= 20 {
+        let bytes = [data[18], data[19]];
+        let machine = if data[5] == 2 {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        };
+        return match machine {
+            62 => Some("x86_64"),
+            183 => Some("aarch64"),
+            3 => Some("x86"),
+            _ => None,
+        };
+    }
+
+    // PE: e_lfanew at 0x3C points at "PE\0\0", followed by the u16 Machine field
+    if data.starts_with(b"MZ") && data.len() >= 0x40 {
+        let pe = u32::from_le_bytes([data[0x3C], data[0x3D], data[0x3E], data[0x3F]]) as usize;
+        if data.len() >= pe + 6 && &data[pe..pe + 4] == b"PE\0\0" {
+            return match u16::from_le_bytes([data[pe + 4], data[pe + 5]]) {
+                0x8664 => Some("x86_64"),
+                0xAA64 => Some("aarch64"),
+                0x014C => Some("x86"),
+                _ => None,
+            };
+        }
+    }
+
+    None
+}
+
+/// Version stamped into a binary: the PE `VS_FIXEDFILEINFO` file version, or the
+/// GNU build ID note for ELF objects, which carry no version resource.
+pub fn binary_version(data: &[u8]) -> Option<String> {
+    if data.starts_with(b"MZ") {
+        // VS_FIXEDFILEINFO starts with this signature; FileVersionMS/LS follow dwStrucVersion.
+        const SIGNATURE: [u8; 4] = 0xFEEF04BDu32.to_le_bytes();
+        let at = data.windows(4).position(|w| w == SIGNATURE)?;
+        let word = |off: usize| -> Option<u32> {
+            let b = data.get(at + off..at + off + 4)?;
+            Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let (ms, ls) = (word(8)?, word(12)?);
+        return Some(format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF));
+    }
+    if data.starts_with(b"\x7fELF") {
+        return elf_build_id(data).map(|id| format!("build-id {}", integrity::to_hex(id)));
+    }
+    None
+}
+
+/// NT_GNU_BUILD_ID descriptor from a little-endian ELF64 object.
+fn elf_build_id(data: &[u8]) -> Option<&[u8]> {
+    if data.get(4) != Some(&2) || data.get(5) != Some(&1) {
+        return None;
+    }
+    let u16_at = |off: usize| data.get(off..off + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let u32_at = |off: usize| {
+        data.get(off..off + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    };
+    let u64_at = |off: usize| {
+        data.get(off..off + 8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap_or_default()) as usize)
+    };
+
+    let shoff = u64_at(0x28)?;
+    let shentsize = u16_at(0x3A)?;
+    let shnum = u16_at(0x3C)?;
+    for i in 0..shnum {
+        let sh = shoff + i * shentsize;
+        // SHT_NOTE
+        if u32_at(sh + 4)? != 7 {
+            continue;
+        }
+        let (mut off, end) = (u64_at(sh + 0x18)?, u64_at(sh + 0x18)? + u64_at(sh + 0x20)?);
+        while off + 12 <= end {
+            let (namesz, descsz, kind) = (u32_at(off)?, u32_at(off + 4)?, u32_at(off + 8)?);
+            let name_start = off + 12;
+            let desc_start = name_start + namesz.div_ceil(4) * 4;
+            if kind == 3 && data.get(name_start..name_start + namesz) == Some(b"GNU\0") {
+                return data.get(desc_start..desc_start + descsz);
+            }
+            off = desc_start + descsz.div_ceil(4) * 4;
+        }
+    }
+    None
+}
+
+/// `UprootedHook/<version>` library entry from a `.deps.json` file.
+fn deps_json_version(data: &[u8]) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_slice(data).ok()?;
+    json.get("libraries")?
+        .as_object()?
+        .keys()
+        .find_map(|k| k.strip_prefix("UprootedHook/"))
+        .map(str::to_string)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct HookVersionInfo {
+    pub profiler_version: Option<String>,
+    pub hook_dll_version: Option<String>,
+    pub preload_js_hash: Option<String>,
+    pub theme_css_hash: Option<String>,
+    /// Components whose deployed version differs from the one embedded in this installer
+    pub drifted: Vec<String>,
+}
+
+fn version_info(read: impl Fn(&str) -> Option<Vec<u8>>) -> HookVersionInfo {
+    HookVersionInfo {
+        profiler_version: read(PROFILER_FILENAME).and_then(|d| binary_version(&d)),
+        hook_dll_version: read("UprootedHook.deps.json").and_then(|d| deps_json_version(&d)),
+        preload_js_hash: read("uprooted-preload.js").map(|d| integrity::sha256_hex(&d)),
+        theme_css_hash: read("uprooted.css").map(|d| integrity::sha256_hex(&d)),
+        drifted: Vec::new(),
+    }
+}
+
+/// Versions and hashes of the deployed hook components, compared against the
+/// embedded artifacts. Missing files are reported as `None`, not as drift.
+pub fn get_hook_versions() -> HookVersionInfo {
+    let dir = get_uprooted_dir();
+    let mut deployed = version_info(|name| fs::read(dir.join(name)).ok());
+
+    let minify = load_settings().minify_css;
+    let embedded = version_info(|name| {
+        let (_, data) = embedded_artifacts().into_iter().find(|(n, _)| *n == name)?;
+        let data = embedded::decompress(data).ok()?;
+        Some(if name == "uprooted.css" {
+            prepare_theme_css(data, minify)
+        } else {
+            data
+        })
+    });
+
+    let pairs = [
+        ("profiler", &deployed.profiler_version, &embedded.profiler_version),
+        ("hook", &deployed.hook_dll_version, &embedded.hook_dll_version),
+        ("preload", &deployed.preload_js_hash, &embedded.preload_js_hash),
+        ("theme css", &deployed.theme_css_hash, &embedded.theme_css_hash),
     ];
+    let drifted = pairs
+        .into_iter()
+        .filter(|(_, have, want)| have.is_some() && have != want)
+        .map(|(name, _, _)| name.to_string())
+        .collect();
+    deployed.drifted = drifted;
+    deployed
+}
+
+/// Deploy all embedded files to the install directory.
+pub fn deploy_files() -> Result<(), UprootedError> {
+    let dir = get_uprooted_dir();
+    fs::create_dir_all(&dir)
+        .map_err(|e| UprootedError::CreateDirFailed(format!("{}: {}", dir.display(), e)))?;
+
+    let settings = load_settings();
+    let deployed = deploy_artifacts(&dir, &settings);
+    if deployed.is_ok() {
+        crate::plugins::deploy_plugin_css(&settings);
+    }
+    // Last, so the directory is explained even when a write above failed.
+    let readme_written = write_readme(&dir);
+
+    let mut names = deployed?;
+    if readme_written {
+        names.push(README_FILE);
+    }
+    integrity::write_manifest(&dir, &names).map_err(UprootedError::DeploymentFailed)?;
+    Ok(())
+}
+
+/// Write every embedded artifact to `dir` and return the names written.
+fn deploy_artifacts(
+    dir: &std::path::Path,
+    settings: &crate::settings::UprootedSettings,
+) -> Result<Vec<&'static str>, UprootedError> {
+    let files = embedded_artifacts();
+    let span = crate::logging::timed(tracing::info_span!(
+        "deploy_files",
+        file_count = files.len(),
+        total_bytes = tracing::field::Empty
+    ));
+    let mut total_bytes = 0;
+
+    for (name, compressed) in &files {
+        if crate::updates::is_applied_update(dir, name) {
+            tracing::info!("keeping updated {}", name);
+            continue;
+        }
+        let mut data = embedded::decompress(compressed)?;
+        tracing::info!(
+            compressed_bytes = compressed.len(),
+            decompressed_bytes = data.len(),
+            "decompressed {} ({:.1}x)",
+            name,
+            data.len() as f64 / compressed.len().max(1) as f64
+        );
+        if *name == "uprooted.css" {
+            data = prepare_theme_css(data, settings.minify_css);
+        }
 
-    for (name, data) in files {
         let path = dir.join(name);
-        fs::write(&path, data)
-            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        fs::write(&path, &data).map_err(|e| {
+            UprootedError::DeploymentFailed(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+        total_bytes += data.len();
     }
+    span.record("total_bytes", total_bytes);
 
     // On Linux, set the profiler .so as executable
     #[cfg(target_os = "linux")]
@@ -92,112 +451,478 @@ pub fn deploy_files() -> Result<(), String> {
         let _ = std::fs::set_permissions(&profiler_path, perms);
     }
 
+    #[cfg(target_os = "windows")]
+    for name in [PROFILER_FILENAME, "UprootedHook.dll"] {
+        check_signature(&dir.join(name), settings.deploy_verify_signatures)?;
+    }
+
+    Ok(files.iter().map(|(name, _)| *name).collect())
+}
+
+/// Write `README.txt` into `dir`. A failure is only logged; the hook works without it.
+fn write_readme(dir: &std::path::Path) -> bool {
+    match fs::write(dir.join(README_FILE), embedded::README_TXT) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Failed to write {}: {}", README_FILE, e);
+            false
+        }
+    }
+}
+
+/// Redeploy a single embedded artifact, e.g. after `uprooted.css` was deleted.
+/// `file_name` must be one of `embedded_artifacts()`, so it can't name another path.
+/// The file is written to a temporary name and renamed into place. `README.txt` is
+/// also accepted and simply rewritten.
+pub fn repair_file(file_name: &str) -> Result<(), UprootedError> {
+    if file_name == README_FILE {
+        let path = get_uprooted_dir().join(README_FILE);
+        return fs::write(&path, embedded::README_TXT).map_err(|e| {
+            UprootedError::DeploymentFailed(format!("Failed to write {}: {}", path.display(), e))
+        });
+    }
+    let (name, compressed) = embedded_artifacts()
+        .into_iter()
+        .find(|(name, _)| *name == file_name)
+        .ok_or_else(|| UprootedError::Io(format!("{} is not an uprooted file", file_name)))?;
+
+    let dir = get_uprooted_dir();
+    fs::create_dir_all(&dir)
+        .map_err(|e| UprootedError::CreateDirFailed(format!("{}: {}", dir.display(), e)))?;
+
+    let settings = load_settings();
+    let mut data = embedded::decompress(compressed)?;
+    if name == "uprooted.css" {
+        data = prepare_theme_css(data, settings.minify_css);
+    }
+
+    let path = dir.join(name);
+    let tmp = dir.join(format!("{}.tmp", name));
+    fs::write(&tmp, &data)
+        .and_then(|_| fs::rename(&tmp, &path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            UprootedError::DeploymentFailed(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+    #[cfg(target_os = "linux")]
+    if name == PROFILER_FILENAME {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o755));
+    }
+
+    #[cfg(target_os = "windows")]
+    if name == PROFILER_FILENAME || name == "UprootedHook.dll" {
+        check_signature(&path, settings.deploy_verify_signatures)?;
+    }
+
+    Ok(())
+}
+
+/// Warn about an unsigned binary, or remove it and fail when `required`.
+#[cfg(target_os = "windows")]
+fn check_signature(path: &std::path::Path, required: bool) -> Result<(), UprootedError> {
+    if verify_authenticode(path) {
+        return Ok(());
+    }
+    if required {
+        let _ = fs::remove_file(path);
+        return Err(UprootedError::SignatureInvalid(path.display().to_string()));
+    }
+    tracing::warn!("{} has no valid signature; continuing", path.display());
     Ok(())
 }
 
+/// Check a file's Authenticode signature with WinVerifyTrust.
+#[cfg(target_os = "windows")]
+fn verify_authenticode(path: &std::path::Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Security::WinTrust::*;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe {
+        let mut file_info: WINTRUST_FILE_INFO = std::mem::zeroed();
+        file_info.cbStruct = std::mem::size_of::<WINTRUST_FILE_INFO>() as u32;
+        file_info.pcwszFilePath = wide.as_ptr();
+
+        let mut data: WINTRUST_DATA = std::mem::zeroed();
+        data.cbStruct = std::mem::size_of::<WINTRUST_DATA>() as u32;
+        data.dwUIChoice = WTD_UI_NONE;
+        data.fdwRevocationChecks = WTD_REVOKE_NONE;
+        data.dwUnionChoice = WTD_CHOICE_FILE;
+        data.Anonymous.pFile = &mut file_info;
+        data.dwStateAction = WTD_STATEACTION_VERIFY;
+
+        let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+        let status = WinVerifyTrust(
+            std::ptr::null_mut(),
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut std::ffi::c_void,
+        );
+
+        // Release the state data allocated by the verify call
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        WinVerifyTrust(
+            std::ptr::null_mut(),
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut std::ffi::c_void,
+        );
+
+        status == 0
+    }
+}
+
+/// The theme stylesheet, minified when requested. Falls back to the original
+/// bytes if the CSS can't be parsed so deployment never fails on it.
+fn prepare_theme_css(raw: Vec<u8>, minify: bool) -> Vec<u8> {
+    if !minify {
+        return raw;
+    }
+    let Ok(source) = std::str::from_utf8(&raw) else {
+        return raw;
+    };
+    match css::minify_css(source) {
+        Ok(minified) => {
+            tracing::debug!(
+                original_bytes = source.len(),
+                minified_bytes = minified.len(),
+                "minified uprooted.css"
+            );
+            minified.into_bytes()
+        }
+        Err(e) => {
+            tracing::warn!("Skipping CSS minification: {}", e);
+            raw
+        }
+    }
+}
+
 // ==================== Windows: environment variables via registry ====================
 
-/// Set CLR profiler environment variables (user-scoped) and broadcast WM_SETTINGCHANGE.
 #[cfg(target_os = "windows")]
-pub fn set_env_vars() -> Result<(), String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let (env_key, _) = hkcu
-        .create_subkey("Environment")
-        .map_err(|e| format!("Failed to open HKCU\\Environment: {}", e))?;
-
-    let profiler_path = get_uprooted_dir()
-        .join("uprooted_profiler.dll")
-        .to_string_lossy()
-        .to_string();
-
-    env_key
-        .set_value("CORECLR_ENABLE_PROFILING", &"1")
-        .map_err(|e| format!("Failed to set CORECLR_ENABLE_PROFILING: {}", e))?;
-    env_key
-        .set_value("CORECLR_PROFILER", &PROFILER_GUID)
-        .map_err(|e| format!("Failed to set CORECLR_PROFILER: {}", e))?;
-    env_key
-        .set_value("CORECLR_PROFILER_PATH", &profiler_path)
-        .map_err(|e| format!("Failed to set CORECLR_PROFILER_PATH: {}", e))?;
-    env_key
-        .set_value("DOTNET_ReadyToRun", &"0")
-        .map_err(|e| format!("Failed to set DOTNET_ReadyToRun: {}", e))?;
-
-    // Remove legacy startup hooks var if present
-    let _ = env_key.delete_value("DOTNET_STARTUP_HOOKS");
+const SYSTEM_ENVIRONMENT_KEY: &str = "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment";
+
+/// Registry key holding the hook env vars for the configured install scope:
+/// `HKCU\Environment` for the user, the session manager key for a system-wide install.
+#[cfg(target_os = "windows")]
+fn env_registry_key() -> (RegKey, &'static str, &'static str) {
+    match load_settings().install_scope {
+        InstallScope::User => {
+            (RegKey::predef(HKEY_CURRENT_USER), "Environment", "HKCU\\Environment")
+        }
+        InstallScope::System => system_env_registry_key(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn system_env_registry_key() -> (RegKey, &'static str, &'static str) {
+    (
+        RegKey::predef(HKEY_LOCAL_MACHINE),
+        SYSTEM_ENVIRONMENT_KEY,
+        "HKLM\\SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment",
+    )
+}
 
+/// Whether env var changes for the configured scope must go through an elevated child.
+#[cfg(target_os = "windows")]
+fn needs_elevation() -> bool {
+    load_settings().install_scope == InstallScope::System && !elevation::is_elevated()
+}
+
+/// Write `vars` under `key` and delete the other hook env vars there.
+#[cfg(target_os = "windows")]
+fn write_hook_env(
+    (root, subkey, label): (RegKey, &str, &str),
+    vars: &[(&str, &str)],
+) -> Result<(), String> {
+    let (env_key, _) = root
+        .create_subkey(subkey)
+        .map_err(|e| format!("Failed to open {}: {}", label, e))?;
+    write_env_values(&env_key, vars)?;
+    for var in ENV_VARS {
+        if !vars.iter().any(|(name, _)| name == var) {
+            let _ = env_key.delete_value(var);
+        }
+    }
     broadcast_env_change();
     Ok(())
 }
 
-/// Remove all Uprooted-related environment variables (user-scoped).
+/// Delete every hook env var under `key`.
 #[cfg(target_os = "windows")]
-pub fn remove_env_vars() -> Result<(), String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let env_key = hkcu
-        .open_subkey_with_flags("Environment", KEY_WRITE)
-        .map_err(|e| format!("Failed to open HKCU\\Environment: {}", e))?;
-
+fn delete_hook_env((root, subkey, label): (RegKey, &str, &str)) -> Result<(), String> {
+    let env_key = root
+        .open_subkey_with_flags(subkey, KEY_WRITE)
+        .map_err(|e| format!("Failed to open {}: {}", label, e))?;
     for var in ENV_VARS {
         let _ = env_key.delete_value(var);
     }
-
     broadcast_env_change();
     Ok(())
 }
 
-/// Check env var status from the registry.
+/// Set the hook environment variables (in the install scope) and broadcast WM_SETTINGCHANGE.
+/// A system-wide install without admin rights hands the values to an elevated child.
+#[cfg(target_os = "windows")]
+pub fn set_env_vars() -> Result<(), String> {
+    let settings = load_settings();
+    if assert_enabled(&settings).is_err() {
+        return Ok(());
+    }
+    let vars = hook_env_vars(settings.hook_mode);
+    let _span = crate::logging::timed(tracing::info_span!("set_env_vars", var_count = vars.len()));
+    apply_env_vars(vars.into_iter().map(|(name, value)| (name.to_string(), value)).collect())
+}
+
+/// Write `vars` as the hook env vars in the install scope, through an elevated child
+/// when the scope needs it.
+#[cfg(target_os = "windows")]
+fn apply_env_vars(vars: Vec<(String, String)>) -> Result<(), String> {
+    if needs_elevation() {
+        return elevation::run_elevated(&ElevatedRequest::SetEnvVars(vars))
+            .map_err(|e| e.to_string());
+    }
+    let pairs: Vec<(&str, &str)> =
+        vars.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+    write_hook_env(env_registry_key(), &pairs)
+}
+
+/// Remove all Uprooted-related environment variables (in the install scope).
+#[cfg(target_os = "windows")]
+pub fn remove_env_vars() -> Result<(), String> {
+    if needs_elevation() {
+        return elevation::run_elevated(&ElevatedRequest::RemoveEnvVars)
+            .map_err(|e| e.to_string());
+    }
+    delete_hook_env(env_registry_key())
+}
+
+/// Write `vars` to the machine-wide environment. Called by the elevated child with
+/// values computed by the parent.
 #[cfg(target_os = "windows")]
-fn check_env_vars() -> (bool, bool, bool, bool) {
+pub fn set_system_env_vars(vars: &[(String, String)]) -> Result<(), String> {
+    let pairs: Vec<(&str, &str)> =
+        vars.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+    write_hook_env(system_env_registry_key(), &pairs)
+}
+
+/// Delete the hook env vars from the machine-wide environment. Called by the elevated child.
+#[cfg(target_os = "windows")]
+pub fn remove_system_env_vars() -> Result<(), String> {
+    delete_hook_env(system_env_registry_key())
+}
+
+#[cfg(target_os = "windows")]
+const REGISTRY_WRITE_TEST: &str = "UPROOTED_WRITE_TEST";
+
+/// Write, read back and delete a sentinel value in `HKCU\Environment`. Group Policy
+/// can make the key read-only on managed machines, which `set_env_vars()` would
+/// otherwise only find out halfway through an install.
+#[cfg(target_os = "windows")]
+pub fn validate_registry_writable() -> Result<(), UprootedError> {
+    use windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED;
+
+    let describe = |e: std::io::Error| {
+        if e.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+            UprootedError::Registry(
+                "Access to HKCU\\Environment was denied. It is probably locked by Group Policy; \
+                 ask your administrator to allow user environment variables."
+                    .to_string(),
+            )
+        } else {
+            UprootedError::Registry(format!("HKCU\\Environment is not writable: {}", e))
+        }
+    };
+
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let env_key = match hkcu.open_subkey("Environment") {
-        Ok(k) => k,
-        Err(_) => return (false, false, false, false),
+    let (key, _) = hkcu
+        .create_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
+        .map_err(describe)?;
+    key.set_value(REGISTRY_WRITE_TEST, &"1").map_err(describe)?;
+    let read_back = key.get_value::<String, _>(REGISTRY_WRITE_TEST);
+    key.delete_value(REGISTRY_WRITE_TEST).map_err(describe)?;
+
+    if read_back.map_err(describe)? != "1" {
+        return Err(UprootedError::Registry(
+            "HKCU\\Environment did not keep a test value; environment variables cannot be set."
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Write several string values through one open key.
+#[cfg(target_os = "windows")]
+fn write_env_values(key: &RegKey, values: &[(&str, &str)]) -> Result<(), String> {
+    for (name, value) in values {
+        key.set_value(name, value)
+            .map_err(|e| format!("Failed to set {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// Every variable in `ENV_VARS` that is currently set, read through one open key.
+#[cfg(target_os = "windows")]
+fn read_env_values(key: &RegKey) -> HashMap<String, String> {
+    ENV_VARS
+        .iter()
+        .filter_map(|var| {
+            key.get_value::<String, _>(var)
+                .ok()
+                .map(|value| (var.to_string(), value))
+        })
+        .collect()
+}
+
+/// Check env var status from the registry.
+#[cfg(target_os = "windows")]
+fn check_env_vars() -> (bool, bool, bool, bool, bool, bool) {
+    let (root, subkey, _) = env_registry_key();
+    let values = match root.open_subkey(subkey) {
+        Ok(k) => read_env_values(&k),
+        Err(_) => return (false, false, false, false, false, false),
     };
+    let get = |name: &str| values.get(name).map(String::as_str);
 
-    let enable: bool = env_key
-        .get_value::<String, _>("CORECLR_ENABLE_PROFILING")
-        .map(|v| v == "1")
-        .unwrap_or(false);
-    let guid: bool = env_key
-        .get_value::<String, _>("CORECLR_PROFILER")
-        .map(|v| v == PROFILER_GUID)
-        .unwrap_or(false);
-    let path: bool = env_key
-        .get_value::<String, _>("CORECLR_PROFILER_PATH")
-        .map(|v| !v.is_empty())
-        .unwrap_or(false);
-    let r2r: bool = env_key
-        .get_value::<String, _>("DOTNET_ReadyToRun")
-        .map(|v| v == "0")
-        .unwrap_or(false);
+    let enable = get("CORECLR_ENABLE_PROFILING") == Some("1");
+    let guid = get("CORECLR_PROFILER") == Some(profiler_guid().as_str());
+    let path = get("CORECLR_PROFILER_PATH").is_some_and(|v| !v.is_empty());
+    let path_valid = get("CORECLR_PROFILER_PATH").is_some_and(is_deployed_profiler_path);
+    let r2r = get("DOTNET_ReadyToRun") == Some("0");
+    let startup_hook = get("DOTNET_STARTUP_HOOKS").is_some_and(|v| v.ends_with("UprootedHook.dll"));
 
-    (enable, guid, path, r2r)
+    (enable, guid, path, path_valid, r2r, startup_hook)
 }
 
-/// Broadcast WM_SETTINGCHANGE so other processes pick up env var changes.
+/// Configured values of the hook env vars, and where they were read from.
+#[cfg(target_os = "windows")]
+fn configured_env_values() -> (HashMap<String, String>, String) {
+    let (root, subkey, label) = env_registry_key();
+    let values = root
+        .open_subkey(subkey)
+        .map(|k| read_env_values(&k))
+        .unwrap_or_default();
+    (values, label.to_string())
+}
+
+/// Broadcast WM_SETTINGCHANGE so other processes pick up env var changes. Returns at
+/// once; a hung window would otherwise block the calling command for seconds.
 #[cfg(target_os = "windows")]
 fn broadcast_env_change() {
-    unsafe {
-        use std::ffi::OsStr;
-        use std::os::windows::ffi::OsStrExt;
+    spawn_env_broadcast(broadcast_settings_change);
+}
 
-        let env: Vec<u16> = OsStr::new("Environment")
-            .encode_wide()
-            .chain(std::iter::once(0))
+/// Upper bound for one broadcast, after which it stops and is logged as incomplete.
+#[cfg(any(target_os = "windows", test))]
+const ENV_BROADCAST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How long a single window gets to answer before the broadcast moves on.
+#[cfg(target_os = "windows")]
+const ENV_BROADCAST_WINDOW_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Threads waiting on a broadcast result that haven't been joined yet.
+#[cfg(any(target_os = "windows", test))]
+static ENV_BROADCASTS: std::sync::Mutex<Vec<std::thread::JoinHandle<()>>> =
+    std::sync::Mutex::new(Vec::new());
+
+/// Run `broadcast` on its own thread, passing it the time budget to stay within. It
+/// returns how many windows were notified, which comes back over a channel and is
+/// logged; the caller doesn't wait for it, and nothing waits past the timeout.
+#[cfg(any(target_os = "windows", test))]
+fn spawn_env_broadcast<F>(broadcast: F)
+where
+    F: FnOnce(std::time::Duration) -> usize + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(broadcast(ENV_BROADCAST_TIMEOUT));
+    });
+    let handle = std::thread::spawn(move || match rx.recv_timeout(ENV_BROADCAST_TIMEOUT) {
+        Ok(notified) => tracing::info!(notified, "WM_SETTINGCHANGE broadcast finished"),
+        Err(_) => tracing::warn!(
+            "WM_SETTINGCHANGE broadcast did not finish within {}s",
+            ENV_BROADCAST_TIMEOUT.as_secs()
+        ),
+    });
+    let mut pending = ENV_BROADCASTS.lock().unwrap_or_else(|e| e.into_inner());
+    pending.retain(|h| !h.is_finished());
+    pending.push(handle);
+}
+
+/// Wait for env var broadcasts still in flight, at most `ENV_BROADCAST_TIMEOUT` each.
+/// Headless runs (`--elevated`, `--check-repair`) call this before exiting, which would
+/// otherwise kill them.
+pub fn wait_for_env_broadcasts() {
+    #[cfg(any(target_os = "windows", test))]
+    {
+        let pending: Vec<_> = ENV_BROADCASTS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
             .collect();
+        for handle in pending {
+            let _ = handle.join();
+        }
+    }
+}
 
-        // HWND_BROADCAST = 0xFFFF, WM_SETTINGCHANGE = 0x001A
-        windows_sys::Win32::UI::WindowsAndMessaging::SendMessageTimeoutW(
-            0xFFFF_usize as *mut std::ffi::c_void,
-            0x001A,
+/// Send WM_SETTINGCHANGE to each top-level window in turn and return how many
+/// answered. Every window gets up to `ENV_BROADCAST_WINDOW_TIMEOUT`, hung ones are
+/// skipped at once, and enumeration stops when `budget` runs out.
+#[cfg(target_os = "windows")]
+fn broadcast_settings_change(budget: std::time::Duration) -> usize {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::time::Instant;
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, SendMessageTimeoutW, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+    };
+
+    struct Broadcast {
+        env: Vec<u16>,
+        deadline: Instant,
+        notified: usize,
+    }
+
+    unsafe extern "system" fn notify(hwnd: HWND, state: LPARAM) -> BOOL {
+        let state = &mut *(state as *mut Broadcast);
+        let remaining = state.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return 0;
+        }
+        let sent = SendMessageTimeoutW(
+            hwnd,
+            WM_SETTINGCHANGE,
             0,
-            env.as_ptr() as isize,
-            0x0002, // SMTO_ABORTIFHUNG
-            5000,
+            state.env.as_ptr() as LPARAM,
+            SMTO_ABORTIFHUNG,
+            remaining.min(ENV_BROADCAST_WINDOW_TIMEOUT).as_millis() as u32,
             std::ptr::null_mut(),
         );
+        if sent != 0 {
+            state.notified += 1;
+        }
+        1
     }
+
+    let mut state = Broadcast {
+        env: OsStr::new("Environment")
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect(),
+        deadline: Instant::now() + budget,
+        notified: 0,
+    };
+    unsafe {
+        EnumWindows(Some(notify), &mut state as *mut Broadcast as LPARAM);
+    }
+    state.notified
 }
 
 // ==================== Linux: wrapper script + .desktop file ====================
@@ -210,9 +935,18 @@ fn broadcast_env_change() {
 /// 3. `.desktop` file -- "Root (Uprooted)" app menu entry using the wrapper
 #[cfg(target_os = "linux")]
 pub fn set_env_vars() -> Result<(), String> {
+    let settings = load_settings();
+    if assert_enabled(&settings).is_err() {
+        return Ok(());
+    }
     let dir = get_uprooted_dir();
-    let profiler_path = dir.join(PROFILER_FILENAME);
     let root_path = crate::detection::get_root_exe_path();
+    let vars = hook_env_vars(settings.hook_mode);
+    let _span = crate::logging::timed(tracing::info_span!("set_env_vars", var_count = vars.len()));
+    let exports: String = vars
+        .iter()
+        .map(|(name, value)| format!("export {}='{}'\n", name, value))
+        .collect();
 
     // 1. systemd environment.d -- session-wide env vars (like Windows registry)
     let home = std::env::var("HOME").unwrap_or_default();
@@ -221,30 +955,38 @@ pub fn set_env_vars() -> Result<(), String> {
         .map_err(|e| format!("Failed to create environment.d: {}", e))?;
 
     let env_conf = format!(
-        "# Uprooted CLR profiler -- remove this file or run the uninstaller to disable\n\
-CORECLR_ENABLE_PROFILING=1\n\
-CORECLR_PROFILER={}\n\
-CORECLR_PROFILER_PATH={}\n\
-DOTNET_ReadyToRun=0\n",
-        PROFILER_GUID,
-        profiler_path.display()
+        "# Uprooted CLR profiler -- remove this file or run the uninstaller to disable\n{}",
+        vars.iter()
+            .map(|(name, value)| format!("{}={}\n", name, value))
+            .collect::<String>()
     );
     fs::write(env_dir.join("uprooted.conf"), &env_conf)
         .map_err(|e| format!("Failed to write environment.d/uprooted.conf: {}", e))?;
 
     // 2. Wrapper script -- works immediately from terminal
     let wrapper = dir.join("launch-root.sh");
+    // Snap apps must be started through `snap run` to get their confinement;
+    // `--shell` keeps our exported vars in the environment Root sees.
+    // Extracted AppImages run the unpacked binary, which needs the bundled libraries.
+    let exec_line = if crate::detection::is_snap_install() {
+        format!(
+            "exec snap run --shell {} -c 'exec \"$SNAP/usr/bin/Root\" \"$@\"' Root \"$@\"",
+            crate::detection::SNAP_NAME
+        )
+    } else if let Some(squashfs) = crate::detection::extracted_appimage_root() {
+        format!(
+            "export LD_LIBRARY_PATH='{}'${{LD_LIBRARY_PATH:+:$LD_LIBRARY_PATH}}\nexec '{}' \"$@\"",
+            squashfs.join("usr/lib").display(),
+            squashfs.join("usr/bin/Root").display()
+        )
+    } else {
+        format!("exec '{}' \"$@\"", root_path.display())
+    };
     let script = format!(
         "#!/bin/bash\n\
 # Uprooted launcher - sets CLR profiler env vars for Root only\n\
-export CORECLR_ENABLE_PROFILING=1\n\
-export CORECLR_PROFILER='{}'\n\
-export CORECLR_PROFILER_PATH='{}'\n\
-export DOTNET_ReadyToRun=0\n\
-exec '{}' \"$@\"\n",
-        PROFILER_GUID,
-        profiler_path.display(),
-        root_path.display()
+{}{}\n",
+        exports, exec_line
     );
     fs::write(&wrapper, &script)
         .map_err(|e| format!("Failed to write wrapper script: {}", e))?;
@@ -260,31 +1002,84 @@ exec '{}' \"$@\"\n",
     create_desktop_file(&wrapper)?;
 
     // 4. ~/.profile fallback -- for non-systemd sessions (X11 login shells, etc.)
+    // Replace any previous block so a hook mode change takes effect here too.
     let profile_path = PathBuf::from(&home).join(".profile");
     let profile_content = fs::read_to_string(&profile_path).unwrap_or_default();
-    if !profile_content.contains("CORECLR_ENABLE_PROFILING") {
-        let block = format!(
-            "\n# Uprooted CLR profiler (remove these lines to disable)\n\
-export CORECLR_ENABLE_PROFILING=1\n\
-export CORECLR_PROFILER='{}'\n\
-export CORECLR_PROFILER_PATH='{}'\n\
-export DOTNET_ReadyToRun=0\n",
-            PROFILER_GUID,
-            profiler_path.display()
-        );
-        let mut file = fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&profile_path)
-            .map_err(|e| format!("Failed to append to ~/.profile: {}", e))?;
-        use std::io::Write;
-        file.write_all(block.as_bytes())
-            .map_err(|e| format!("Failed to write to ~/.profile: {}", e))?;
-    }
+    let profile_content = if profile_content.contains(PROFILE_BLOCK_MARKER) {
+        strip_profile_block(&profile_content)
+    } else {
+        profile_content
+    };
+    let block = format!(
+        "\n{} (remove these lines to disable)\n{}",
+        PROFILE_BLOCK_MARKER, exports
+    );
+    fs::write(&profile_path, profile_content + &block)
+        .map_err(|e| format!("Failed to write to ~/.profile: {}", e))?;
 
     Ok(())
 }
 
+#[cfg(target_os = "linux")]
+const PROFILE_BLOCK_MARKER: &str = "# Uprooted CLR profiler";
+
+/// What `set_env_vars()` writes, as (purpose, path, present now). `~/.profile` only
+/// gets a block appended, so it counts as present while that block is there.
+#[cfg(target_os = "linux")]
+pub fn env_var_files() -> Vec<(&'static str, PathBuf, bool)> {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    let profile = home.join(".profile");
+    let profile_block = fs::read_to_string(&profile)
+        .is_ok_and(|content| content.contains(PROFILE_BLOCK_MARKER));
+    let files = [
+        ("Session environment (systemd)", home.join(".config/environment.d/uprooted.conf")),
+        ("Launcher script", get_uprooted_dir().join("launch-root.sh")),
+        (
+            "\"Root (Uprooted)\" app menu entry",
+            home.join(".local/share/applications/root-uprooted.desktop"),
+        ),
+    ];
+    files
+        .into_iter()
+        .map(|(purpose, path)| {
+            let present = path.exists();
+            (purpose, path, present)
+        })
+        .chain(std::iter::once((
+            "`export` block for login shells",
+            profile,
+            profile_block,
+        )))
+        .collect()
+}
+
+/// Remove the installer's block (marker comment + export lines) from `~/.profile` content.
+#[cfg(target_os = "linux")]
+fn strip_profile_block(content: &str) -> String {
+    let cleaned: Vec<&str> = content
+        .lines()
+        .scan(false, |in_block, line| {
+            if line.contains(PROFILE_BLOCK_MARKER) {
+                *in_block = true;
+                Some(None) // skip this line
+            } else if *in_block && (line.starts_with("export CORECLR_")
+                || line.starts_with("export DOTNET_")
+                || line.is_empty())
+            {
+                if !line.starts_with("export") {
+                    *in_block = false;
+                }
+                Some(None) // skip
+            } else {
+                *in_block = false;
+                Some(Some(line))
+            }
+        })
+        .flatten()
+        .collect();
+    cleaned.join("\n") + "\n"
+}
+
 /// Remove all env var mechanisms: environment.d, wrapper script, .desktop file.
 #[cfg(target_os = "linux")]
 pub fn remove_env_vars() -> Result<(), String> {
@@ -307,57 +1102,24 @@ pub fn remove_env_vars() -> Result<(), String> {
     // Remove env vars from ~/.profile if present
     let profile_path = PathBuf::from(&home).join(".profile");
     if let Ok(content) = fs::read_to_string(&profile_path) {
-        if content.contains("CORECLR_ENABLE_PROFILING") {
-            // Remove the Uprooted block (comment + 4 export lines + blank line before)
-            let cleaned: Vec<&str> = content
-                .lines()
-                .collect::<Vec<_>>()
-                .into_iter()
-                .scan(false, |in_block, line| {
-                    if line.contains("# Uprooted CLR profiler") {
-                        *in_block = true;
-                        Some(None) // skip this line
-                    } else if *in_block && (line.starts_with("export CORECLR_")
-                        || line.starts_with("export DOTNET_")
-                        || line.is_empty())
-                    {
-                        if !line.starts_with("export") {
-                            *in_block = false;
-                        }
-                        Some(None) // skip
-                    } else {
-                        *in_block = false;
-                        Some(Some(line))
-                    }
-                })
-                .flatten()
-                .collect();
-            let _ = fs::write(&profile_path, cleaned.join("\n") + "\n");
+        if content.contains(PROFILE_BLOCK_MARKER) {
+            let _ = fs::write(&profile_path, strip_profile_block(&content));
         }
     }
 
     Ok(())
 }
 
-/// Create a .desktop file that launches Root through the wrapper script.
+/// Create a .desktop file that launches Root through the wrapper script, using
+/// the `desktop_file` settings.
 #[cfg(target_os = "linux")]
-fn create_desktop_file(wrapper: &PathBuf) -> Result<(), String> {
+fn create_desktop_file(wrapper: &std::path::Path) -> Result<(), String> {
     let home = std::env::var("HOME").unwrap_or_default();
     let apps_dir = PathBuf::from(&home).join(".local/share/applications");
     fs::create_dir_all(&apps_dir)
         .map_err(|e| format!("Failed to create applications dir: {}", e))?;
 
-    let desktop_content = format!(
-        "[Desktop Entry]\n\
-Name=Root (Uprooted)\n\
-Comment=Root Communications with Uprooted mods\n\
-Exec={}\n\
-Type=Application\n\
-Categories=Network;Chat;\n\
-Terminal=false\n",
-        wrapper.display()
-    );
-
+    let desktop_content = desktop_entry(&load_settings().desktop_file, wrapper);
     let desktop_file = apps_dir.join("root-uprooted.desktop");
     fs::write(&desktop_file, &desktop_content)
         .map_err(|e| format!("Failed to write .desktop file: {}", e))?;
@@ -373,9 +1135,56 @@ Terminal=false\n",
     Ok(())
 }
 
+#[cfg(target_os = "linux")]
+fn desktop_entry(config: &DesktopFileConfig, wrapper: &std::path::Path) -> String {
+    // Entry values are single-line; a newline would start a new key.
+    let value = |s: &str| s.replace(['\n', '\r'], " ");
+    let icon = config.icon_path.clone().or_else(|| {
+        let default_icon = get_uprooted_dir().join("icon.png");
+        default_icon
+            .exists()
+            .then(|| default_icon.to_string_lossy().to_string())
+    });
+
+    let mut entry = format!("[Desktop Entry]\nName={}\n", value(&config.display_name));
+    if let Some(comment) = &config.comment {
+        entry += &format!("Comment={}\n", value(comment));
+    }
+    if let Some(icon) = icon {
+        entry += &format!("Icon={}\n", value(&icon));
+    }
+    entry += &format!(
+        "Exec={}\nType=Application\nCategories={}\nTerminal=false\n",
+        wrapper.display(),
+        config
+            .categories
+            .iter()
+            .map(|c| format!("{};", value(c)))
+            .collect::<String>()
+    );
+    entry
+}
+
+/// Save the desktop entry settings and, if the wrapper is installed, rewrite the
+/// entry so the app menu picks up the change.
+pub fn set_desktop_file_config(config: DesktopFileConfig) -> Result<(), String> {
+    let mut settings = load_settings();
+    settings.desktop_file = config;
+    save_settings(&settings)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let wrapper = get_uprooted_dir().join("launch-root.sh");
+        if wrapper.exists() {
+            create_desktop_file(&wrapper)?;
+        }
+    }
+    Ok(())
+}
+
 /// Check env var status from environment.d config (falls back to wrapper script).
 #[cfg(target_os = "linux")]
-fn check_env_vars() -> (bool, bool, bool, bool) {
+fn check_env_vars() -> (bool, bool, bool, bool, bool, bool) {
     let home = std::env::var("HOME").unwrap_or_default();
 
     // Check environment.d first (primary mechanism)
@@ -393,11 +1202,47 @@ fn check_env_vars() -> (bool, bool, bool, bool) {
         .unwrap_or_default();
 
     let enable = content.contains("CORECLR_ENABLE_PROFILING=1");
-    let guid = content.contains(PROFILER_GUID);
+    let guid = content.contains(&profiler_guid());
     let path = content.contains("CORECLR_PROFILER_PATH=");
+    let path_valid = content.lines().any(|line| {
+        line.trim_start_matches("export ")
+            .strip_prefix("CORECLR_PROFILER_PATH=")
+            .is_some_and(|v| is_deployed_profiler_path(v.trim_matches('\'')))
+    });
     let r2r = content.contains("DOTNET_ReadyToRun=0");
+    let startup_hook = content.lines().any(|line| {
+        line.trim_start_matches("export ")
+            .strip_prefix("DOTNET_STARTUP_HOOKS=")
+            .is_some_and(|v| v.trim_matches('\'').ends_with("UprootedHook.dll"))
+    });
 
-    (enable, guid, path, r2r)
+    (enable, guid, path, path_valid, r2r, startup_hook)
+}
+
+/// Configured values of the hook env vars, read from the same files as
+/// `check_env_vars()` in the same order, and the file they came from.
+#[cfg(target_os = "linux")]
+fn configured_env_values() -> (HashMap<String, String>, String) {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    let candidates = [
+        home.join(".config/environment.d/uprooted.conf"),
+        get_uprooted_dir().join("launch-root.sh"),
+        home.join(".profile"),
+    ];
+    for path in &candidates {
+        if let Ok(content) = fs::read_to_string(path) {
+            let values = content
+                .lines()
+                .filter_map(|line| {
+                    let (name, value) = line.trim_start_matches("export ").split_once('=')?;
+                    (name.starts_with("CORECLR_") || name.starts_with("DOTNET_"))
+                        .then(|| (name.to_string(), value.trim_matches('\'').to_string()))
+                })
+                .collect();
+            return (values, path.display().to_string());
+        }
+    }
+    (HashMap::new(), candidates[0].display().to_string())
 }
 
 // ==================== Common: runtime env var check ====================
@@ -407,25 +1252,51 @@ fn check_env_vars() -> (bool, bool, bool, bool) {
 /// the config check. On Linux, environment.d only takes effect after re-login,
 /// so this detects the gap between "configured" and "actually active".
 #[cfg(target_os = "windows")]
-fn check_env_vars_active() -> bool {
+fn check_env_vars_active(mode: HookMode) -> bool {
     // On Windows, if the config (registry) says env vars are set, they'll be
     // active for any newly launched process. Return true if configured.
-    let (enable, guid, path, _) = check_env_vars();
-    enable && guid && path
+    let (enable, guid, _, path_valid, _, startup_hook) = check_env_vars();
+    env_vars_ok(mode, enable, guid, path_valid, startup_hook)
 }
 
 #[cfg(target_os = "linux")]
-fn check_env_vars_active() -> bool {
+fn check_env_vars_active(mode: HookMode) -> bool {
     let enable = std::env::var("CORECLR_ENABLE_PROFILING")
         .map(|v| v == "1")
         .unwrap_or(false);
     let guid = std::env::var("CORECLR_PROFILER")
-        .map(|v| v == PROFILER_GUID)
+        .map(|v| v == profiler_guid())
         .unwrap_or(false);
     let path = std::env::var("CORECLR_PROFILER_PATH")
-        .map(|v| !v.is_empty())
+        .map(|v| is_deployed_profiler_path(&v))
+        .unwrap_or(false);
+    let startup_hook = std::env::var("DOTNET_STARTUP_HOOKS")
+        .map(|v| v.ends_with("UprootedHook.dll"))
         .unwrap_or(false);
-    enable && guid && path
+    env_vars_ok(mode, enable, guid, path, startup_hook)
+}
+
+/// Every env var `set_env_vars()` would write for the configured hook mode, next to
+/// its current configured value. Nothing is written.
+pub fn list_env_vars() -> Vec<EnvVarInfo> {
+    env_var_infos(load_settings().hook_mode)
+}
+
+fn env_var_infos(mode: HookMode) -> Vec<EnvVarInfo> {
+    let (current, scope) = configured_env_values();
+    hook_env_vars(mode)
+        .into_iter()
+        .map(|(key, intended_value)| {
+            let current_value = current.get(key).cloned();
+            EnvVarInfo {
+                key: key.to_string(),
+                is_set_correctly: current_value.as_deref() == Some(intended_value.as_str()),
+                intended_value,
+                current_value,
+                scope: scope.clone(),
+            }
+        })
+        .collect()
 }
 
 // ==================== Common: file operations ====================
@@ -440,8 +1311,147 @@ pub fn remove_files() -> Result<(), String> {
     Ok(())
 }
 
-/// Check per-file and per-env-var status.
+/// Deployed files and hook env vars as they were before a staged install, so
+/// cancelling it puts an earlier install back instead of removing it.
+pub struct HookSnapshot {
+    /// Top-level files in the uprooted directory, or `None` if it didn't exist.
+    files: Option<crate::storage::FileSnapshot>,
+    #[cfg(target_os = "windows")]
+    env: HashMap<String, String>,
+    #[cfg(target_os = "linux")]
+    env: crate::storage::FileSnapshot,
+}
+
+impl HookSnapshot {
+    pub fn take() -> Self {
+        let dir = get_uprooted_dir();
+        let files = fs::read_dir(&dir).ok().map(|entries| {
+            let paths = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file());
+            crate::storage::snapshot_files(paths)
+        });
+        #[cfg(target_os = "windows")]
+        let env = configured_env_values().0;
+        #[cfg(target_os = "linux")]
+        let env = crate::storage::snapshot_files(env_var_files().into_iter().map(|(_, p, _)| p));
+        HookSnapshot { files, env }
+    }
+
+    /// Put the deployed files back, deleting the ones added since.
+    pub fn restore_files(&self) -> Result<(), String> {
+        let Some(files) = &self.files else {
+            return remove_files();
+        };
+        let dir = get_uprooted_dir();
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_file() && !files.iter().any(|(p, _)| *p == path) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+        crate::storage::restore_snapshot(files)
+    }
+
+    /// Put the hook env vars back as they were.
+    #[cfg(target_os = "windows")]
+    pub fn restore_env(&self) -> Result<(), String> {
+        if self.env.is_empty() {
+            return remove_env_vars();
+        }
+        apply_env_vars(self.env.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
+
+    /// Put the env var files back as they were.
+    #[cfg(target_os = "linux")]
+    pub fn restore_env(&self) -> Result<(), String> {
+        crate::storage::restore_snapshot(&self.env)
+    }
+}
+
+fn check_profiler_arch_mismatch(dir: &std::path::Path) -> bool {
+    let Ok(data) = fs::read(dir.join(PROFILER_FILENAME)) else {
+        return false;
+    };
+    binary_arch(&data).is_some_and(|arch| arch != native_arch())
+}
+
+/// Sizes of the deployed artifacts, and whether any looks truncated compared to the
+/// embedded copy. `uprooted.css` is skipped since deployment may minify it.
+fn deployed_file_sizes(dir: &std::path::Path) -> (HashMap<String, u64>, bool) {
+    let mut sizes = HashMap::new();
+    let mut truncated = false;
+    for (name, _) in embedded_artifacts() {
+        let Ok(meta) = fs::metadata(dir.join(name)) else {
+            continue;
+        };
+        let size = meta.len();
+        if name != "uprooted.css" {
+            if let Some(expected) = artifact_size(name) {
+                truncated |= size < expected as u64 * 9 / 10;
+            }
+        }
+        sizes.insert(name.to_string(), size);
+    }
+    (sizes, truncated)
+}
+
+/// Size and modification time of each file in the uprooted directory, by name.
+type DeployedFingerprint = Vec<(std::ffi::OsString, u64, Option<std::time::SystemTime>)>;
+
+fn deployed_fingerprint(dir: &std::path::Path) -> DeployedFingerprint {
+    let mut files: DeployedFingerprint = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some((entry.file_name(), meta.len(), meta.modified().ok()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Tampered files and the profiler arch check from the last full status check, with
+/// the fingerprint they were computed for.
+static CONTENT_CHECKS: std::sync::Mutex<Option<(DeployedFingerprint, Vec<String>, bool)>> =
+    std::sync::Mutex::new(None);
+
+/// Hash the deployed files and read the profiler's architecture, unless `reuse` is set
+/// and no deployed file changed size or mtime since the last time.
+fn content_checks(dir: &std::path::Path, reuse: bool) -> (Vec<String>, bool) {
+    let fingerprint = deployed_fingerprint(dir);
+    let mut cached = CONTENT_CHECKS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((seen, tampered, arch_mismatch)) = cached.as_ref() {
+        if reuse && *seen == fingerprint {
+            return (tampered.clone(), *arch_mismatch);
+        }
+    }
+    let tampered = integrity::tampered_files();
+    let arch_mismatch = check_profiler_arch_mismatch(dir);
+    *cached = Some((fingerprint, tampered.clone(), arch_mismatch));
+    (tampered, arch_mismatch)
+}
+
+/// Check per-file and per-env-var status, hashing every deployed file.
 pub fn check_hook_status() -> HookStatus {
+    build_hook_status(false)
+}
+
+/// `check_hook_status()` for the status poll: only file sizes, mtimes and env vars are
+/// read, and the hash and architecture checks are redone when a deployed file changed.
+pub fn check_hook_status_cached() -> HookStatus {
+    build_hook_status(true)
+}
+
+fn build_hook_status(reuse_content_checks: bool) -> HookStatus {
+    let span = crate::logging::timed(tracing::info_span!(
+        "check_hook_status",
+        files_ok = tracing::field::Empty,
+        env_ok = tracing::field::Empty
+    ));
     let dir = get_uprooted_dir();
 
     let profiler_dll = dir.join(PROFILER_FILENAME).exists();
@@ -450,12 +1460,27 @@ pub fn check_hook_status() -> HookStatus {
     let preload_js = dir.join("uprooted-preload.js").exists();
     let theme_css = dir.join("uprooted.css").exists();
 
-    let (env_enable, env_guid, env_path, env_r2r) = check_env_vars();
+    let (env_enable, env_guid, env_path, env_path_valid, env_r2r, env_startup_hook) =
+        check_env_vars();
 
+    let settings = load_settings();
+    let hook_mode = settings.hook_mode;
     let files_ok = profiler_dll && hook_dll && hook_deps && preload_js && theme_css;
-    let env_ok = env_enable && env_guid && env_path;
+    let env_ok = env_vars_ok(
+        hook_mode,
+        env_enable,
+        env_guid,
+        env_path_valid,
+        env_startup_hook,
+    );
 
-    let env_vars_active = check_env_vars_active();
+    span.record("files_ok", files_ok);
+    span.record("env_ok", env_ok);
+
+    let env_vars_active = check_env_vars_active(hook_mode);
+    let receipt = receipt::read_receipt();
+    let (file_sizes, files_possibly_truncated) = deployed_file_sizes(&dir);
+    let (tampered_files, dll_arch_mismatch) = content_checks(&dir, reuse_content_checks);
 
     HookStatus {
         profiler_dll,
@@ -466,11 +1491,191 @@ pub fn check_hook_status() -> HookStatus {
         env_enable_profiling: env_enable,
         env_profiler_guid: env_guid,
         env_profiler_path: env_path,
+        env_profiler_path_valid: env_path_valid,
         env_ready_to_run: env_r2r,
+        env_startup_hook,
         files_ok,
         env_ok,
         env_vars_active,
+        tampered_files,
+        dll_arch_mismatch,
+        installed_at: receipt.as_ref().map(|r| r.installed_at.clone()),
+        installer_version: receipt.as_ref().map(|r| r.uprooted_version.clone()),
+        last_repaired_at: receipt.and_then(|r| r.last_repaired_at),
+        html_patching_disabled: hook_mode == HookMode::EnvOnlyNoHtml,
+        env_vars: env_var_infos(hook_mode),
+        settings_enabled: settings.enabled,
+        file_sizes,
+        files_possibly_truncated,
+    }
+}
+
+/// Traffic-light level for a hook status field. Ordered from best to worst.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HookSeverity {
+    Ok,
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HookSeverityReport {
+    pub overall: HookSeverity,
+    pub per_field: HashMap<String, HookSeverity>,
+}
+
+/// Rate each status field by how badly a failure affects Root: a missing profiler or
+/// hook stops uprooted loading at all, a missing stylesheet is only cosmetic.
+pub fn hook_status_severity(status: &HookStatus) -> HookSeverityReport {
+    let rules = [
+        ("profiler_dll", status.profiler_dll, HookSeverity::Critical),
+        ("hook_dll", status.hook_dll, HookSeverity::Critical),
+        ("hook_deps", status.hook_deps, HookSeverity::Warning),
+        ("preload_js", status.preload_js, HookSeverity::Warning),
+        ("theme_css", status.theme_css, HookSeverity::Info),
+        ("env_ok", status.env_ok, HookSeverity::Critical),
+    ];
+    let per_field: HashMap<String, HookSeverity> = rules
+        .into_iter()
+        .map(|(name, ok, if_missing)| {
+            (name.to_string(), if ok { HookSeverity::Ok } else { if_missing })
+        })
+        .collect();
+    let overall = per_field.values().copied().max().unwrap_or(HookSeverity::Ok);
+    HookSeverityReport { overall, per_field }
+}
+
+/// Which part of the hook chain a `HookEntry` describes.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    ClrProfiler,
+    StartupHook,
+    JsPreload,
+    StyleSheet,
+    EnvironmentVar,
+}
+
+/// One facet of the hook, for a single table in the UI instead of `HookStatus` flags.
+#[derive(Serialize, Clone)]
+pub struct HookEntry {
+    pub kind: HookKind,
+    pub name: String,
+    pub path: String,
+    pub is_active: bool,
+    pub details: HashMap<String, String>,
+}
+
+fn hook_entry(
+    kind: HookKind,
+    name: &str,
+    path: &std::path::Path,
+    is_active: bool,
+) -> HookEntry {
+    HookEntry {
+        kind,
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        is_active,
+        details: HashMap::new(),
+    }
+}
+
+/// Every hook component: the profiler, the startup hook, each script and stylesheet
+/// loaded into Root (uprooted's own and those of enabled plugins), and each env var.
+pub fn get_active_hooks() -> Vec<HookEntry> {
+    let status = check_hook_status();
+    let settings = load_settings();
+    let dir = get_uprooted_dir();
+    let html_patched = !status.html_patching_disabled
+        && crate::detection::check_is_installed(&crate::detection::find_target_html_files());
+    let profiler_mode = settings.hook_mode != HookMode::StartupHookOnly;
+
+    let mut entries = Vec::new();
+
+    let profiler_active = profiler_mode && status.profiler_dll && status.env_ok;
+    let mut profiler = hook_entry(
+        HookKind::ClrProfiler,
+        "CLR profiler",
+        &dir.join(PROFILER_FILENAME),
+        profiler_active,
+    );
+    profiler.details.insert("guid".to_string(), profiler_guid());
+    if status.dll_arch_mismatch {
+        profiler
+            .details
+            .insert("warning".to_string(), "built for another CPU architecture".to_string());
+    }
+    entries.push(profiler);
+
+    let mut startup_hook = hook_entry(
+        HookKind::StartupHook,
+        "Startup hook",
+        &dir.join("UprootedHook.dll"),
+        status.hook_dll && status.hook_deps && status.env_ok,
+    );
+    let loaded_by = if profiler_mode { "CLR profiler" } else { "DOTNET_STARTUP_HOOKS" };
+    startup_hook.details.insert("loaded_by".to_string(), loaded_by.to_string());
+    entries.push(startup_hook);
+
+    let mut preload = hook_entry(
+        HookKind::JsPreload,
+        "uprooted-preload.js",
+        &dir.join("uprooted-preload.js"),
+        status.preload_js && html_patched,
+    );
+    preload.details.insert(
+        "version".to_string(),
+        crate::artifact_meta::PRELOAD_JS_VERSION.to_string(),
+    );
+    entries.push(preload);
+
+    let plugins = crate::plugins::list_available_plugins();
+    for plugin in plugins
+        .iter()
+        .filter(|p| crate::plugins::is_enabled(&settings, &p.manifest.name))
+    {
+        let Some(script) = &plugin.entry_js_path else {
+            continue;
+        };
+        let mut entry = hook_entry(
+            HookKind::JsPreload,
+            &plugin.manifest.name,
+            std::path::Path::new(script),
+            html_patched,
+        );
+        entry.details.insert("plugin".to_string(), plugin.manifest.name.clone());
+        entry.details.insert("version".to_string(), plugin.manifest.version.clone());
+        entries.push(entry);
     }
+
+    entries.push(hook_entry(
+        HookKind::StyleSheet,
+        "uprooted.css",
+        &dir.join("uprooted.css"),
+        status.theme_css && html_patched,
+    ));
+    for (plugin_name, css) in crate::plugins::named_plugin_css_files(&settings) {
+        let mut entry = hook_entry(HookKind::StyleSheet, &plugin_name, &css, html_patched);
+        entry.details.insert("plugin".to_string(), plugin_name);
+        entries.push(entry);
+    }
+
+    for var in status.env_vars {
+        let mut entry = hook_entry(
+            HookKind::EnvironmentVar,
+            &var.key,
+            std::path::Path::new(&var.scope),
+            var.is_set_correctly,
+        );
+        entry.details.insert("intended_value".to_string(), var.intended_value);
+        if let Some(current) = var.current_value {
+            entry.details.insert("current_value".to_string(), current);
+        }
+        entries.push(entry);
+    }
+
+    entries
 }
 
 // ==================== Process management ====================
@@ -525,6 +1730,206 @@ pub fn kill_root_processes() -> u32 {
     }
 }
 
+/// Close Root and start it again with the hook env vars: through `launch-root.sh`
+/// on Linux, or with the vars passed directly on Windows, where processes started
+/// from Explorer may not have picked up the registry change yet.
+pub fn relaunch_via_wrapper() -> Result<(), String> {
+    // Checked before closing Root, so a missing launcher doesn't leave it closed.
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        let wrapper = get_uprooted_dir().join("launch-root.sh");
+        if !wrapper.exists() {
+            return Err("The launcher script is missing. Repair uprooted first.".to_string());
+        }
+        std::process::Command::new(wrapper)
+    };
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let root_exe = crate::detection::get_root_exe_path();
+        if !root_exe.exists() {
+            return Err(format!("Root was not found at {}.", root_exe.display()));
+        }
+        let mut command = std::process::Command::new(root_exe);
+        command.envs(hook_env_vars(load_settings().hook_mode));
+        command
+    };
+
+    kill_root_processes();
+    for _ in 0..20 {
+        if !check_root_running() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start Root: {}", e))
+}
+
+/// How the running Root was started, judged by its environment.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchMethod {
+    /// With the hook env vars: through the wrapper script, or a session that has them.
+    Wrapper,
+    /// Without them, e.g. from Root's own launcher entry.
+    Direct,
+    /// Root isn't running, or its environment couldn't be read.
+    Unknown,
+}
+
+/// Whether any running Root process has the hook env vars in its environment.
+pub fn detect_launch_method() -> LaunchMethod {
+    let readable: Vec<Vec<String>> = root_process_environments().into_iter().flatten().collect();
+    if readable.is_empty() {
+        return LaunchMethod::Unknown;
+    }
+
+    let profiler = format!("CORECLR_PROFILER={}", profiler_guid());
+    let hooked = readable.iter().any(|env| {
+        env.iter().any(|var| {
+            *var == profiler
+                || (var.starts_with("DOTNET_STARTUP_HOOKS=") && var.contains("UprootedHook.dll"))
+        })
+    });
+    if hooked {
+        LaunchMethod::Wrapper
+    } else {
+        LaunchMethod::Direct
+    }
+}
+
+/// `NAME=value` entries of each running Root process, `None` where they couldn't be read.
+#[cfg(target_os = "linux")]
+fn root_process_environments() -> Vec<Option<Vec<String>>> {
+    let Ok(output) = std::process::Command::new("pgrep").arg("-x").arg("Root").output() else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|pid| {
+            let environ = fs::read(format!("/proc/{}/environ", pid.trim())).ok()?;
+            Some(
+                environ
+                    .split(|&b| b == 0)
+                    .filter(|var| !var.is_empty())
+                    .map(|var| String::from_utf8_lossy(var).to_string())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn root_process_environments() -> Vec<Option<Vec<String>>> {
+    let root_exe = crate::detection::get_root_exe_path();
+    find_root_pids()
+        .into_iter()
+        .map(|pid| read_process_environment(pid, &root_exe))
+        .collect()
+}
+
+/// Read another process's environment block through its PEB. Skips processes
+/// whose image isn't `expected_image`, so an unrelated `Root.exe` is ignored.
+/// Uses the x64 layout of `RTL_USER_PROCESS_PARAMETERS`; Root only ships 64-bit.
+#[cfg(target_os = "windows")]
+fn read_process_environment(pid: u32, expected_image: &std::path::Path) -> Option<Vec<String>> {
+    use windows_sys::Wdk::System::Threading::{NtQueryInformationProcess, ProcessBasicInformation};
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_BASIC_INFORMATION, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+    };
+
+    const PEB_PROCESS_PARAMETERS_OFFSET: usize = 0x20;
+    const PARAMS_ENVIRONMENT_OFFSET: usize = 0x80;
+    const PARAMS_ENVIRONMENT_SIZE_OFFSET: usize = 0x3F0;
+    const MAX_ENVIRONMENT_BYTES: usize = 1 << 20;
+
+    if cfg!(not(target_pointer_width = "64")) {
+        return None;
+    }
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        let read = |address: usize, buf: &mut [u8]| -> bool {
+            let mut bytes_read = 0usize;
+            ReadProcessMemory(
+                handle,
+                address as *const std::ffi::c_void,
+                buf.as_mut_ptr() as *mut std::ffi::c_void,
+                buf.len(),
+                &mut bytes_read,
+            ) != 0
+                && bytes_read == buf.len()
+        };
+        let read_usize = |address: usize| -> Option<usize> {
+            let mut buf = [0u8; 8];
+            read(address, &mut buf).then(|| u64::from_le_bytes(buf) as usize)
+        };
+
+        let result = (|| {
+            let mut image = [0u16; 1024];
+            let mut image_len = image.len() as u32;
+            let ok = QueryFullProcessImageNameW(
+                handle,
+                PROCESS_NAME_WIN32,
+                image.as_mut_ptr(),
+                &mut image_len,
+            );
+            if ok == 0 {
+                return None;
+            }
+            let image = String::from_utf16_lossy(&image[..image_len as usize]);
+            if !image.eq_ignore_ascii_case(&expected_image.to_string_lossy()) {
+                return None;
+            }
+
+            let mut info: PROCESS_BASIC_INFORMATION = std::mem::zeroed();
+            let status = NtQueryInformationProcess(
+                handle,
+                ProcessBasicInformation,
+                &mut info as *mut _ as *mut std::ffi::c_void,
+                std::mem::size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+                std::ptr::null_mut(),
+            );
+            if status < 0 {
+                return None;
+            }
+
+            let params = read_usize(info.PebBaseAddress as usize + PEB_PROCESS_PARAMETERS_OFFSET)?;
+            let environment = read_usize(params + PARAMS_ENVIRONMENT_OFFSET)?;
+            let size = read_usize(params + PARAMS_ENVIRONMENT_SIZE_OFFSET)?
+                .min(MAX_ENVIRONMENT_BYTES)
+                & !1;
+            let mut block = vec![0u8; size];
+            if !read(environment, &mut block) {
+                return None;
+            }
+
+            let wide: Vec<u16> = block
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            Some(
+                wide.split(|&c| c == 0)
+                    .take_while(|var| !var.is_empty())
+                    .map(String::from_utf16_lossy)
+                    .collect(),
+            )
+        })();
+
+        CloseHandle(handle);
+        result
+    }
+}
+
 /// Find all PIDs for Root.exe (Windows only).
 #[cfg(target_os = "windows")]
 fn find_root_pids() -> Vec<u32> {
@@ -562,3 +1967,43 @@ fn find_root_pids() -> Vec<u32> {
     }
     pids
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn env_broadcast_returns_before_the_broadcast_finishes() {
+        let started = Instant::now();
+        spawn_env_broadcast(|_| {
+            std::thread::sleep(Duration::from_secs(2));
+            0
+        });
+        assert!(started.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn env_broadcast_gets_the_full_timeout() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        spawn_env_broadcast(move |timeout| {
+            let _ = tx.send(timeout);
+            0
+        });
+        let timeout = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(timeout, ENV_BROADCAST_TIMEOUT);
+    }
+
+    #[test]
+    fn waiting_for_env_broadcasts_joins_them() {
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = done.clone();
+        spawn_env_broadcast(move |_| {
+            std::thread::sleep(Duration::from_millis(200));
+            flag.store(true, std::sync::atomic::Ordering::SeqCst);
+            3
+        });
+        wait_for_env_broadcasts();
+        assert!(done.load(std::sync::atomic::Ordering::SeqCst));
+    }
+}
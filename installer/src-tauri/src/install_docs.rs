@@ -0,0 +1,251 @@
+//! Markdown description of what uprooted changes on this machine, for IT change
+//! management. Assembled from the same detection and status data the UI shows.
+
+#[cfg(target_os = "linux")]
+use crate::autostart;
+use crate::detection;
+use crate::hook;
+use crate::service;
+use crate::settings::{self, BackupNaming, HookMode};
+use crate::system;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Keep the shape of an env var value but not its content: paths under the uprooted
+/// directory keep their file name, everything else has letters and digits masked.
+fn redact(value: &str, uprooted_dir: &str) -> String {
+    match value.strip_prefix(uprooted_dir) {
+        Some(rest) if !uprooted_dir.is_empty() => format!("<uprooted dir>{}", rest),
+        _ => value
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { 'x' } else { c })
+            .collect(),
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// What the installer adds to start its monitor or Root, as (entry, location,
+/// present now).
+#[cfg(target_os = "windows")]
+fn startup_entries() -> Vec<(&'static str, String, bool)> {
+    vec![(
+        "Logon entry running the installer with `--check-repair`",
+        format!("`HKCU\\{}`, value `{}`", service::RUN_KEY, service::TASK_NAME),
+        service::is_scheduled_task_installed(),
+    )]
+}
+
+#[cfg(target_os = "linux")]
+fn startup_entries() -> Vec<(&'static str, String, bool)> {
+    let mut entries = vec![
+        (
+            "Monitor service (systemd user unit)",
+            format!("`{}`", service::unit_path().display()),
+            service::get_service_status().installed,
+        ),
+        (
+            "Monitor autostart entry, for desktops without systemd",
+            format!("`{}`", autostart::autostart_path().display()),
+            autostart::is_autostart_enabled(),
+        ),
+    ];
+    entries.extend(
+        hook::env_var_files()
+            .into_iter()
+            .map(|(purpose, path, present)| (purpose, format!("`{}`", path.display()), present)),
+    );
+    entries
+}
+
+/// The full document, describing the current state of this machine.
+pub fn render_install_docs() -> String {
+    let info = system::get_system_info();
+    let status = hook::check_hook_status();
+    let settings = settings::load_settings();
+    let uprooted_dir = hook::get_uprooted_dir();
+    let uprooted_dir_str = uprooted_dir.to_string_lossy().to_string();
+    let env_vars = hook::list_env_vars();
+    let html_files = detection::list_target_files();
+    let backup_name = match settings.backup_naming {
+        BackupNaming::Simple => "<file>.uprooted.bak",
+        BackupNaming::Timestamped => "<file>.YYYYMMDD_HHMMSS.uprooted.bak",
+    };
+
+    // Writing to a String can't fail.
+    let mut doc = String::new();
+    let _ = writeln!(doc, "# Uprooted installation\n");
+    let _ = writeln!(
+        doc,
+        "Generated {} by the Uprooted installer {}. Uprooted adds themes and plugins to \
+         the Root desktop app. It does not modify Root's executables.\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let _ = writeln!(doc, "## System\n");
+    let _ = writeln!(doc, "| | |\n|---|---|");
+    let _ = writeln!(doc, "| Operating system | {} ({}) |", info.os, info.arch);
+    let _ = writeln!(
+        doc,
+        "| Root version | {} |",
+        info.root_version.as_deref().unwrap_or("unknown")
+    );
+    let _ = writeln!(
+        doc,
+        "| .NET runtime | {} |",
+        info.dotnet_version.as_deref().unwrap_or("not found")
+    );
+    let _ = writeln!(doc, "| Snap package | {} |", yes_no(info.is_snap));
+    let _ = writeln!(doc, "| WSL | {} |", yes_no(info.in_wsl));
+    let _ = writeln!(
+        doc,
+        "| Hook mode | {} |",
+        match settings.hook_mode {
+            HookMode::Profiler => "CLR profiler",
+            HookMode::StartupHookOnly => ".NET startup hook",
+            HookMode::EnvOnlyNoHtml => "CLR profiler, HTML left unpatched",
+        }
+    );
+    let _ = writeln!(
+        doc,
+        "| Installed | {} |\n",
+        status.installed_at.as_deref().unwrap_or("no")
+    );
+
+    let _ = writeln!(doc, "## Deployed files\n");
+    let _ = writeln!(doc, "All files are written to `{}`.\n", uprooted_dir.display());
+    if status.file_sizes.is_empty() {
+        let _ = writeln!(doc, "No files are deployed yet.\n");
+    } else {
+        let mut files: Vec<_> = status.file_sizes.iter().collect();
+        files.sort();
+        let _ = writeln!(doc, "| File | Size (bytes) |\n|---|---|");
+        for (name, size) in files {
+            let _ = writeln!(doc, "| `{}` | {} |", uprooted_dir.join(name).display(), size);
+        }
+        let _ = writeln!(doc);
+    }
+
+    let scope = env_vars.first().map(|v| v.scope.clone()).unwrap_or_default();
+    let _ = writeln!(doc, "## Environment variables\n");
+    let _ = writeln!(
+        doc,
+        "Set for Root's .NET runtime in `{}`. Values are redacted to their format.\n",
+        scope
+    );
+    let _ = writeln!(doc, "| Variable | Value format | Currently set |\n|---|---|---|");
+    for var in &env_vars {
+        let _ = writeln!(
+            doc,
+            "| `{}` | `{}` | {} |",
+            var.key,
+            redact(&var.intended_value, &uprooted_dir_str),
+            yes_no(var.is_set_correctly)
+        );
+    }
+    let _ = writeln!(doc);
+
+    let _ = writeln!(doc, "## Configuration files\n");
+    let _ = writeln!(doc, "- `{}`: the environment variables above", scope);
+    let _ = writeln!(
+        doc,
+        "- `{}`: installer settings (theme, plugins, custom CSS)",
+        settings::settings_path().display()
+    );
+    for log in [crate::logging::log_path(), crate::logging::json_log_path()] {
+        let _ = writeln!(doc, "- `{}`: installer log", log.display());
+    }
+    let _ = writeln!(doc);
+
+    let _ = writeln!(doc, "## Startup and launcher entries\n");
+    let _ = writeln!(doc, "| Entry | Location | Present |\n|---|---|---|");
+    for (entry, location, present) in startup_entries() {
+        let _ = writeln!(doc, "| {} | {} | {} |", entry, location, yes_no(present));
+    }
+    let _ = writeln!(doc);
+
+    let _ = writeln!(doc, "## Patched HTML files\n");
+    if status.html_patching_disabled {
+        let _ = writeln!(doc, "HTML patching is turned off; no Root files are modified.\n");
+    } else if html_files.is_empty() {
+        let _ = writeln!(
+            doc,
+            "No Root HTML files were found. Root creates them on first launch.\n"
+        );
+    } else {
+        let _ = writeln!(
+            doc,
+            "A block between `<!-- uprooted:start -->` and `<!-- uprooted:end -->` is added \
+             before `</head>`. A copy of each original is kept next to it as `{}`{}.\n",
+            backup_name,
+            if settings.backup_naming == BackupNaming::Timestamped {
+                ", a new one each time the file is patched"
+            } else {
+                ""
+            }
+        );
+        let _ = writeln!(doc, "| File | Patched | Backup |\n|---|---|---|");
+        for file in &html_files {
+            let _ = writeln!(
+                doc,
+                "| `{}` | {} | {} |",
+                file.path,
+                yes_no(file.is_patched),
+                yes_no(file.backup_exists)
+            );
+        }
+        let _ = writeln!(doc);
+    }
+
+    let _ = writeln!(doc, "## Reverting\n");
+    let _ = writeln!(
+        doc,
+        "Run the Uprooted installer and choose **Uninstall**, then restart Root. It:\n"
+    );
+    if cfg!(target_os = "windows") {
+        let _ = writeln!(doc, "1. Removes the logon entry listed above.");
+        let _ = writeln!(doc, "2. Removes the environment variables from `{}`.", scope);
+    } else {
+        let _ = writeln!(
+            doc,
+            "1. Stops and removes the monitor service and deletes the autostart entry."
+        );
+        let _ = writeln!(
+            doc,
+            "2. Deletes the environment, launcher and app menu files listed above and removes \
+             the `export` block from `~/.profile`."
+        );
+    }
+    let _ = writeln!(
+        doc,
+        "3. Removes the uprooted block from each patched HTML file, or restores the latest \
+         `{}` backup if the block can't be found, then deletes its backups.",
+        backup_name
+    );
+    let _ = writeln!(doc, "4. Deletes `{}`.\n", uprooted_dir.display());
+    let _ = writeln!(
+        doc,
+        "To revert by hand, do the same steps with Root closed. The settings file \
+         and logs listed above can be deleted afterwards. {}",
+        if cfg!(target_os = "windows") {
+            "Environment variable changes apply to programs started after the change."
+        } else {
+            "Environment variable changes apply after logging out and back in."
+        }
+    );
+    doc
+}
+
+/// Write `render_install_docs()` to `path`.
+pub fn write_install_docs(path: &Path) -> Result<(), String> {
+    fs::write(path, render_install_docs())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
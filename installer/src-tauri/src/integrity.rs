@@ -0,0 +1,198 @@
+//! `manifest.json` lists the SHA-256 of each deployed file so a truncated write, a
+//! partial update or an AV scanner rewriting a file can be spotted and repaired.
+//!
+//! This detects accidental corruption only, not tampering. The HMAC key is derived
+//! from a constant and the build timestamp, both readable from the installer binary,
+//! so anyone who can write the deployed files can also re-sign the manifest.
+
+use crate::hook::get_uprooted_dir;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const BUILD_TIMESTAMP: &str = env!("UPROOTED_BUILD_TIMESTAMP");
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ManifestFile {
+    pub name: String,
+    pub sha256: String,
+}
+
+/// `manifest.json` written next to the deployed files.
+#[derive(Serialize, Deserialize)]
+pub struct DeployManifest {
+    pub version: String,
+    pub timestamp: String,
+    pub files: Vec<ManifestFile>,
+    pub hmac: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FileIntegrity {
+    pub name: String,
+    pub expected_sha256: String,
+    pub actual_sha256: Option<String>,
+    pub ok: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct IntegrityReport {
+    pub manifest_found: bool,
+    pub hmac_valid: bool,
+    pub files: Vec<FileIntegrity>,
+    pub all_ok: bool,
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+fn sha256_file(path: &Path) -> Option<String> {
+    fs::read(path).ok().map(|data| sha256_hex(&data))
+}
+
+/// Not a secret: see the module docs.
+fn manifest_key() -> Vec<u8> {
+    Sha256::digest(format!("uprooted-deploy-manifest:{}", BUILD_TIMESTAMP)).to_vec()
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn manifest_mac(version: &str, timestamp: &str, files: &[ManifestFile]) -> HmacSha256 {
+    let payload = serde_json::json!({
+        "version": version,
+        "timestamp": timestamp,
+        "files": files,
+    })
+    .to_string();
+    let mut mac =
+        HmacSha256::new_from_slice(&manifest_key()).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+fn sign(version: &str, timestamp: &str, files: &[ManifestFile]) -> String {
+    to_hex(&manifest_mac(version, timestamp, files).finalize().into_bytes())
+}
+
+/// Check the manifest's MAC in constant time.
+fn verify(manifest: &DeployManifest) -> bool {
+    from_hex(&manifest.hmac).is_some_and(|tag| {
+        manifest_mac(&manifest.version, &manifest.timestamp, &manifest.files)
+            .verify_slice(&tag)
+            .is_ok()
+    })
+}
+
+/// Hash the named files in `dir` and write a signed `manifest.json` alongside them.
+pub fn write_manifest(dir: &Path, names: &[&str]) -> Result<(), String> {
+    let mut files = Vec::new();
+    for name in names {
+        let sha256 = sha256_file(&dir.join(name))
+            .ok_or_else(|| format!("Failed to hash {}", dir.join(name).display()))?;
+        files.push(ManifestFile {
+            name: name.to_string(),
+            sha256,
+        });
+    }
+
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let hmac = sign(&version, &timestamp, &files);
+    let manifest = DeployManifest {
+        version,
+        timestamp,
+        files,
+        hmac,
+    };
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(dir.join(MANIFEST_FILE), json)
+        .map_err(|e| format!("Failed to write {}: {}", MANIFEST_FILE, e))
+}
+
+/// Recompute hashes of deployed files and check them against the signed manifest.
+pub fn verify_deployed_integrity() -> IntegrityReport {
+    let dir = get_uprooted_dir();
+    let manifest: Option<DeployManifest> = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+
+    let manifest = match manifest {
+        Some(m) => m,
+        None => {
+            return IntegrityReport {
+                manifest_found: false,
+                hmac_valid: false,
+                files: vec![],
+                all_ok: false,
+            }
+        }
+    };
+
+    let hmac_valid = verify(&manifest);
+    let files: Vec<FileIntegrity> = manifest
+        .files
+        .iter()
+        .map(|f| {
+            let actual = sha256_file(&dir.join(&f.name));
+            FileIntegrity {
+                name: f.name.clone(),
+                expected_sha256: f.sha256.clone(),
+                ok: actual.as_deref() == Some(f.sha256.as_str()),
+                actual_sha256: actual,
+            }
+        })
+        .collect();
+    let all_ok = hmac_valid && files.iter().all(|f| f.ok);
+
+    IntegrityReport {
+        manifest_found: true,
+        hmac_valid,
+        files,
+        all_ok,
+    }
+}
+
+/// Names of deployed files whose contents no longer match the manifest, plus
+/// `manifest.json` itself when its signature doesn't verify (files and manifest
+/// rewritten together).
+pub fn tampered_files() -> Vec<String> {
+    let report = verify_deployed_integrity();
+    let mut tampered: Vec<String> = report
+        .files
+        .into_iter()
+        .filter(|f| !f.ok)
+        .map(|f| f.name)
+        .collect();
+    if report.manifest_found && !report.hmac_valid {
+        tampered.push(MANIFEST_FILE.to_string());
+    }
+    tampered
+}
+
+/// Installer version recorded in the deploy manifest, if one exists.
+pub fn deployed_version() -> Option<String> {
+    let content = fs::read_to_string(get_uprooted_dir().join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str::<DeployManifest>(&content)
+        .ok()
+        .map(|m| m.version)
+}
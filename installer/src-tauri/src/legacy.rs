@@ -0,0 +1,199 @@
+use crate::detection::find_target_html_files;
+use crate::patcher;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Serialize)]
+pub struct LegacyInstallInfo {
+    pub found: bool,
+    pub locations: Vec<String>,
+}
+
+/// Env vars the bash installer exported from shell rc files.
+#[cfg(target_os = "linux")]
+const LEGACY_EXPORTS: &[&str] = &[
+    "export CORECLR_ENABLE_PROFILING=",
+    "export CORECLR_PROFILER=",
+    "export CORECLR_PROFILER_PATH=",
+    "export DOTNET_ReadyToRun=",
+    "export DOTNET_STARTUP_HOOKS=",
+];
+
+/// Files the legacy exports point at. A run of exports only counts as the legacy installer's
+/// block if one of its paths names one of these, so a user's own profiler setup is left alone.
+#[cfg(target_os = "linux")]
+const LEGACY_TARGETS: &[&str] = &["libuprooted_profiler.so", "UprootedHook.dll"];
+
+/// Line ranges of the legacy installer's blocks in `lines`: consecutive `LEGACY_EXPORTS` lines
+/// naming a `LEGACY_TARGETS` file, plus the uprooted comment directly above them if present.
+#[cfg(target_os = "linux")]
+fn legacy_blocks(lines: &[&str]) -> Vec<std::ops::Range<usize>> {
+    let is_export = |line: &str| {
+        let line = line.trim_start();
+        LEGACY_EXPORTS.iter().any(|e| line.starts_with(e))
+    };
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if !is_export(lines[i]) {
+            i += 1;
+            continue;
+        }
+        let mut end = i;
+        while end < lines.len() && is_export(lines[end]) {
+            end += 1;
+        }
+        let ours = lines[i..end]
+            .iter()
+            .any(|line| LEGACY_TARGETS.iter().any(|t| line.contains(t)));
+        if ours {
+            let header = i > 0
+                && lines[i - 1].trim_start().starts_with('#')
+                && lines[i - 1].to_lowercase().contains("uprooted");
+            blocks.push(if header { i - 1 } else { i }..end);
+        }
+        i = end;
+    }
+    blocks
+}
+
+/// `content` without the legacy installer's blocks, or `None` if it has none.
+#[cfg(target_os = "linux")]
+fn strip_legacy_blocks(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let blocks = legacy_blocks(&lines);
+    if blocks.is_empty() {
+        return None;
+    }
+    let kept: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !blocks.iter().any(|b| b.contains(i)))
+        .map(|(_, line)| *line)
+        .collect();
+    Some(kept.join("\n") + "\n")
+}
+
+/// Shell rc files with uprooted exports not written by the current installer.
+/// `~/.profile` only counts if it lacks the current installer's block comment.
+#[cfg(target_os = "linux")]
+fn legacy_shell_configs() -> Vec<PathBuf> {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    [".bashrc", ".profile"]
+        .iter()
+        .map(|name| home.join(name))
+        .filter(|path| {
+            let content = fs::read_to_string(path).unwrap_or_default();
+            !content.contains("# Uprooted CLR profiler")
+                && strip_legacy_blocks(&content).is_some()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn legacy_deploy_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Early Windows builds deployed to `%APPDATA%\uprooted` instead of `%LOCALAPPDATA%\Root\uprooted`.
+#[cfg(target_os = "windows")]
+fn legacy_deploy_dirs() -> Vec<PathBuf> {
+    let app_data = std::env::var("APPDATA").unwrap_or_default();
+    let dir = PathBuf::from(app_data).join("uprooted");
+    if dir.is_dir() {
+        vec![dir]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn legacy_shell_configs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+fn legacy_html_files() -> Vec<PathBuf> {
+    find_target_html_files()
+        .into_iter()
+        .filter(|f| {
+            fs::read_to_string(f)
+                .map(|c| patcher::is_legacy_injection(&c))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+pub fn detect_legacy_install() -> LegacyInstallInfo {
+    let locations: Vec<String> = legacy_shell_configs()
+        .into_iter()
+        .chain(legacy_deploy_dirs())
+        .chain(legacy_html_files())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    LegacyInstallInfo {
+        found: !locations.is_empty(),
+        locations,
+    }
+}
+
+/// Remove everything the legacy installer left behind. The caller runs a fresh install afterwards.
+pub fn clean_legacy_install() -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    for path in legacy_shell_configs() {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if let Some(cleaned) = strip_legacy_blocks(&content) {
+            fs::write(&path, cleaned)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+    }
+
+    for dir in legacy_deploy_dirs() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))?;
+    }
+
+    for file in legacy_html_files() {
+        let content = fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        fs::write(&file, patcher::strip_injection(&content))
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_only_the_legacy_block() {
+        let content = "# my uprooted notes\n\
+export CORECLR_ENABLE_PROFILING=1\n\
+export CORECLR_PROFILER_PATH=/opt/other/profiler.so\n\
+alias ll='ls -l'\n\
+# Uprooted (legacy)\n\
+export CORECLR_ENABLE_PROFILING=1\n\
+export CORECLR_PROFILER_PATH='/home/u/.local/share/uprooted/libuprooted_profiler.so'\n\
+export DOTNET_ReadyToRun=0\n\
+export PATH=$PATH:~/bin\n";
+        assert_eq!(
+            strip_legacy_blocks(content).as_deref(),
+            Some(
+                "# my uprooted notes\n\
+export CORECLR_ENABLE_PROFILING=1\n\
+export CORECLR_PROFILER_PATH=/opt/other/profiler.so\n\
+alias ll='ls -l'\n\
+export PATH=$PATH:~/bin\n"
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_unrelated_profiler_exports_alone() {
+        let content = "export CORECLR_ENABLE_PROFILING=1\nexport CORECLR_PROFILER_PATH=/opt/x.so\n";
+        assert_eq!(strip_legacy_blocks(content), None);
+    }
+}
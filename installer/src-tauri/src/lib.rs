@@ -0,0 +1,31 @@
+//! Installer core: detection, deployment, patching and settings. The Tauri
+//! commands in `main.rs` are thin wrappers over these modules.
+
+pub mod artifact_meta;
+pub mod autostart;
+pub mod compat;
+pub mod css;
+pub mod daemon;
+pub mod detection;
+pub mod elevation;
+pub mod embedded;
+pub mod embedded_arm64;
+pub mod embedded_changelog;
+pub mod encoding;
+pub mod error;
+pub mod health;
+pub mod hook;
+pub mod install_docs;
+pub mod integrity;
+pub mod legacy;
+pub mod logging;
+pub mod patcher;
+pub mod plugins;
+pub mod prerequisites;
+pub mod receipt;
+pub mod service;
+pub mod settings;
+pub mod storage;
+pub mod system;
+pub mod themes;
+pub mod updates;
@@ -0,0 +1,212 @@
+use crate::detection::get_profile_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::Write;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Log level override, e.g. `UPROOTED_LOG=trace` to also log how long each operation takes.
+pub const LOG_LEVEL_ENV: &str = "UPROOTED_LOG";
+
+/// `uprooted.jsonl` is moved to `uprooted.jsonl.1` at startup once it grows past this.
+const JSON_LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Installer log, kept next to the hook's `uprooted-hook.log`.
+pub fn log_path() -> PathBuf {
+    get_profile_dir().join("uprooted.log")
+}
+
+/// The same events as `log_path()`, one JSON object per line.
+pub fn json_log_path() -> PathBuf {
+    get_profile_dir().join("uprooted.jsonl")
+}
+
+fn rotated_json_log_path() -> PathBuf {
+    get_profile_dir().join("uprooted.jsonl.1")
+}
+
+/// Route `tracing` output to the text log and the JSON log. Skipped if Root's profile
+/// doesn't exist yet, so launching the installer never creates Root's directories.
+pub fn init() {
+    let path = log_path();
+    if !path.parent().map(|p| p.exists()).unwrap_or(false) {
+        return;
+    }
+    let file = match fs::OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(f) => f,
+        Err(_) => return,
+    };
+    let text = tracing_subscriber::fmt::layer()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false);
+    let _ = tracing_subscriber::registry()
+        .with(text)
+        .with(open_json_log().map(JsonLayer))
+        .with(LevelFilter::from_level(max_level()))
+        .try_init();
+}
+
+/// Open the JSON log for appending, rotating it first if it is too large.
+fn open_json_log() -> Option<Mutex<fs::File>> {
+    let path = json_log_path();
+    if fs::metadata(&path).is_ok_and(|m| m.len() > JSON_LOG_MAX_BYTES) {
+        let _ = fs::rename(&path, rotated_json_log_path());
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()
+        .map(Mutex::new)
+}
+
+/// One line of `uprooted.jsonl`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+    pub module: String,
+    /// Every other field on the event, plus `span` when it happened inside one.
+    pub fields: Map<String, Value>,
+}
+
+/// Collects an event's `message` and its other fields as JSON values.
+#[derive(Default)]
+struct JsonVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn record(&mut self, field: &Field, value: Value) {
+        match value {
+            Value::String(message) if field.name() == "message" => self.message = message,
+            value => {
+                self.fields.insert(field.name().to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record(field, Value::String(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, Value::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, value.into());
+    }
+}
+
+/// Writes each event to `uprooted.jsonl` as a `LogEntry`.
+struct JsonLayer(Mutex<fs::File>);
+
+impl<S> Layer<S> for JsonLayer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        if let Some(span) = ctx.event_span(event) {
+            visitor
+                .fields
+                .insert("span".to_string(), Value::String(span.name().to_string()));
+        }
+
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            message: visitor.message,
+            module: metadata.module_path().unwrap_or(metadata.target()).to_string(),
+            fields: visitor.fields,
+        };
+        if let Ok(line) = serde_json::to_string(&entry) {
+            let mut file = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// The newest `limit` entries from the JSON log (including the rotated file), oldest
+/// first. Lines that don't parse, such as one cut short by a crash, are skipped.
+pub fn parse_log_entries(limit: u32) -> Vec<LogEntry> {
+    let limit = limit as usize;
+    let mut entries: Vec<LogEntry> = [rotated_json_log_path(), json_log_path()]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    entries.drain(..skip);
+    entries
+}
+
+fn max_level() -> tracing::Level {
+    std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .and_then(|level| level.trim().parse().ok())
+        .unwrap_or(tracing::Level::INFO)
+}
+
+/// An entered span that logs `duration_ms` when dropped, if trace logging is on.
+pub struct TimedSpan {
+    span: tracing::span::EnteredSpan,
+    start: Instant,
+}
+
+pub fn timed(span: tracing::Span) -> TimedSpan {
+    TimedSpan {
+        span: span.entered(),
+        start: Instant::now(),
+    }
+}
+
+impl Deref for TimedSpan {
+    type Target = tracing::Span;
+
+    fn deref(&self) -> &tracing::Span {
+        &self.span
+    }
+}
+
+impl Drop for TimedSpan {
+    fn drop(&mut self) {
+        if tracing::enabled!(tracing::Level::TRACE) {
+            let duration_ms = u64::try_from(self.start.elapsed().as_millis()).unwrap_or(u64::MAX);
+            tracing::info!(duration_ms, "finished");
+        }
+    }
+}
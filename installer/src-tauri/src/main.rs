@@ -1,22 +1,91 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod detection;
-mod embedded;
-mod hook;
-mod patcher;
-mod settings;
-mod themes;
-
-use detection::DetectionResult;
+use uprooted_installer::{
+    artifact_meta, autostart, css, daemon, detection, elevation, embedded_changelog, error,
+    health, hook, install_docs, integrity, legacy, logging, patcher, plugins, prerequisites,
+    receipt, service, settings, storage, system, themes, updates,
+};
+
+use css::CssError;
+use crossbeam_channel::{bounded, select, tick, Sender};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{Emitter, Listener, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use detection::{DetectionCache, DetectionResult, HtmlFileInfo, InstallState, RootAppInfo};
 use hook::HookStatus;
+use integrity::IntegrityReport;
+use error::UprootedError;
 use patcher::PatchResult;
+use plugins::PluginInfo;
+use prerequisites::PrerequisiteCheck;
 use settings::UprootedSettings;
 use themes::ThemeDefinition;
+use updates::ArtifactUpdate;
+
+#[tauri::command]
+fn detect_root(cache: tauri::State<DetectionCache>) -> DetectionResult {
+    let ttl = std::time::Duration::from_millis(settings::load_settings().detection_cache_ttl_ms);
+    cache.get_or_detect(ttl)
+}
+
+#[tauri::command]
+fn invalidate_detection_cache(cache: tauri::State<DetectionCache>) {
+    cache.invalidate();
+}
+
+#[tauri::command]
+fn list_target_files() -> Vec<HtmlFileInfo> {
+    detection::list_target_files()
+}
+
+#[tauri::command]
+fn list_root_apps() -> Vec<RootAppInfo> {
+    detection::list_root_apps()
+}
+
+#[tauri::command]
+fn scan_for_foreign_injections() -> Vec<patcher::ForeignInjectionReport> {
+    patcher::scan_for_foreign_injections()
+}
+
+#[tauri::command]
+fn preview_injection_string() -> patcher::InjectionPreview {
+    patcher::preview_injection()
+}
+
+/// Write the injection to `debug_injection.html` in the uprooted directory and
+/// return its path.
+#[tauri::command]
+fn dump_injection_to_file() -> Result<String, String> {
+    patcher::dump_injection()
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn open_injection_debug() -> Result<(), String> {
+    let path = dump_injection_to_file()?;
+    opener::open(path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn check_prerequisites() -> Vec<PrerequisiteCheck> {
+    prerequisites::check_prerequisites()
+}
 
 #[tauri::command]
-fn detect_root() -> DetectionResult {
-    detection::detect()
+fn get_system_info() -> system::SystemInfo {
+    system::get_system_info()
+}
+
+/// Write a Markdown summary of everything uprooted changes on this machine.
+#[tauri::command]
+fn generate_install_docs(output_path: String) -> Result<(), String> {
+    install_docs::write_install_docs(std::path::Path::new(&output_path))
 }
 
 #[tauri::command]
@@ -24,88 +93,794 @@ fn check_hook_status() -> HookStatus {
     hook::check_hook_status()
 }
 
+/// Stop signal for the running hook status poll, if any.
+#[derive(Default)]
+struct HookStatusPoll(Mutex<Option<Sender<()>>>);
+
+impl HookStatusPoll {
+    /// Stop the running poll. Returns false if none was running.
+    fn stop(&self) -> bool {
+        match self.0.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(stop) => {
+                let _ = stop.send(());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Shortest poll interval; each check reads the deployed files and env vars.
+const MIN_HOOK_STATUS_POLL_MS: u64 = 250;
+
+/// Check the hook status every `interval_ms` and emit `uprooted://hook-status-changed`
+/// whenever it differs from the previous check (the first check always emits).
+/// Deployed files are only re-hashed when one changes. Starting again replaces the
+/// running poll.
+#[tauri::command]
+fn start_hook_status_poll(
+    interval_ms: u64,
+    window: tauri::Window,
+    poll: tauri::State<HookStatusPoll>,
+) -> Result<(), String> {
+    if interval_ms < MIN_HOOK_STATUS_POLL_MS {
+        return Err(format!(
+            "The poll interval must be at least {} ms.",
+            MIN_HOOK_STATUS_POLL_MS
+        ));
+    }
+    let (stop_tx, stop_rx) = bounded(1);
+    if let Some(previous) = poll.0.lock().unwrap_or_else(|e| e.into_inner()).replace(stop_tx) {
+        let _ = previous.send(());
+    }
+
+    std::thread::spawn(move || {
+        let ticker = tick(std::time::Duration::from_millis(interval_ms));
+        let mut previous = String::new();
+        loop {
+            let status = hook::check_hook_status_cached();
+            let json = serde_json::to_string(&status).unwrap_or_default();
+            if json != previous {
+                if window.emit("uprooted://hook-status-changed", &status).is_err() {
+                    return;
+                }
+                previous = json;
+            }
+            select! {
+                recv(stop_rx) -> _ => return,
+                recv(ticker) -> _ => {}
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_hook_status_poll(poll: tauri::State<HookStatusPoll>) -> Result<(), String> {
+    if poll.stop() {
+        Ok(())
+    } else {
+        Err("No hook status poll is running.".to_string())
+    }
+}
+
+#[tauri::command]
+fn get_active_hooks() -> Vec<hook::HookEntry> {
+    hook::get_active_hooks()
+}
+
+#[tauri::command]
+fn list_env_vars() -> Vec<hook::EnvVarInfo> {
+    hook::list_env_vars()
+}
+
+#[tauri::command]
+fn get_hook_severity() -> hook::HookSeverityReport {
+    hook::hook_status_severity(&hook::check_hook_status())
+}
+
+#[tauri::command]
+fn get_health_score() -> health::HealthScore {
+    health::get_health_score()
+}
+
+#[tauri::command]
+fn get_hook_versions() -> hook::HookVersionInfo {
+    hook::get_hook_versions()
+}
+
+#[tauri::command]
+fn verify_deployed_integrity() -> IntegrityReport {
+    integrity::verify_deployed_integrity()
+}
+
 #[tauri::command]
 fn check_root_running() -> bool {
     hook::check_root_running()
 }
 
+#[tauri::command]
+fn is_read_only() -> bool {
+    storage::is_read_only_mode()
+}
+
+#[tauri::command]
+fn repair_file(file_name: String) -> Result<(), String> {
+    hook::repair_file(&file_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn relaunch_via_wrapper() -> Result<(), String> {
+    hook::relaunch_via_wrapper()
+}
+
 #[tauri::command]
 fn kill_root() -> u32 {
     hook::kill_root_processes()
 }
 
+#[derive(Default)]
+struct OperationState {
+    current_operation: Option<String>,
+    started_at: Option<std::time::Instant>,
+}
+
+/// The command changing deployed or Root files right now (install, repair, uninstall,
+/// plugin or theme changes), so only one runs at a time and the UI can keep its
+/// buttons disabled until it returns.
+#[derive(Default)]
+struct CurrentOperation(Mutex<OperationState>);
+
+impl CurrentOperation {
+    /// Record `label` as running until the returned guard is dropped. Fails while
+    /// another operation holds it.
+    fn begin(&self, label: &str) -> Result<OperationGuard<'_>, UprootedError> {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = &state.current_operation {
+            return Err(UprootedError::OperationRunning(running.clone()));
+        }
+        state.current_operation = Some(label.to_string());
+        state.started_at = Some(std::time::Instant::now());
+        Ok(OperationGuard(self))
+    }
+}
+
+struct OperationGuard<'a>(&'a CurrentOperation);
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0 .0.lock().unwrap_or_else(|e| e.into_inner());
+        if let (Some(label), Some(started_at)) =
+            (state.current_operation.take(), state.started_at.take())
+        {
+            tracing::info!("{} done in {} ms", label, started_at.elapsed().as_millis());
+        }
+    }
+}
+
+/// Async so it is answered while a blocking command holds the main thread.
+#[tauri::command]
+async fn get_current_operation(app: tauri::AppHandle) -> Option<String> {
+    let operation = app.state::<CurrentOperation>();
+    let state = operation.0.lock().unwrap_or_else(|e| e.into_inner());
+    state.current_operation.clone()
+}
+
+#[tauri::command]
+fn install_uprooted(
+    app: tauri::AppHandle,
+    operation: tauri::State<CurrentOperation>,
+) -> PatchResult {
+    let _operation = match operation.begin("Installing...") {
+        Ok(guard) => guard,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    with_upgrade_event(&app, || run_install(false))
+}
+
+/// Install, but keep patching the remaining HTML files when some can't be written
+/// (e.g. read-only RootApps), so the main bundle still gets patched.
 #[tauri::command]
-fn install_uprooted() -> PatchResult {
+fn install_uprooted_tolerant(
+    app: tauri::AppHandle,
+    operation: tauri::State<CurrentOperation>,
+) -> PatchResult {
+    let _operation = match operation.begin("Installing...") {
+        Ok(guard) => guard,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    with_upgrade_event(&app, || run_install(true))
+}
+
+#[derive(Serialize, Clone)]
+struct UpgradedEvent {
+    from_version: String,
+    to_version: String,
+    changelog: String,
+}
+
+/// Run `operation` and, if it upgraded an older install, emit `uprooted://upgraded`
+/// with the changelog since that version for a "What's new" dialog.
+fn with_upgrade_event(
+    app: &tauri::AppHandle,
+    operation: impl FnOnce() -> PatchResult,
+) -> PatchResult {
+    let previous = detection::detect_install_state();
+    let result = operation();
+    if let InstallState::InstalledOlderVersion(from_version) = previous {
+        if result.success {
+            let to_version = env!("CARGO_PKG_VERSION").to_string();
+            let changelog = embedded_changelog::get_changelog_range(&from_version, &to_version);
+            let _ = app.emit(
+                "uprooted://upgraded",
+                UpgradedEvent {
+                    from_version,
+                    to_version,
+                    changelog,
+                },
+            );
+        }
+    }
+    result
+}
+
+/// System-wide installs write machine-wide env vars through UAC, which only exists on Windows.
+#[cfg(not(target_os = "windows"))]
+fn check_install_scope(settings: &UprootedSettings) -> Result<(), UprootedError> {
+    if settings.install_scope == settings::InstallScope::System {
+        return Err(UprootedError::PrerequisiteFailed(
+            "System-wide installs are only supported on Windows".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn check_install_scope(_settings: &UprootedSettings) -> Result<(), UprootedError> {
+    Ok(())
+}
+
+/// Fail `result` if any file it patched doesn't read back with a complete injection.
+fn validate_patched_files(result: &mut PatchResult) {
+    let files: Vec<std::path::PathBuf> = result.files_patched.iter().map(Into::into).collect();
+    let invalid = patcher::validate_install(&files);
+    if invalid.is_empty() {
+        return;
+    }
+
+    let details: Vec<String> = invalid.iter().map(ToString::to_string).collect();
+    tracing::error!("post-install validation failed: {}", details.join("; "));
+    let error = UprootedError::PatchFailed(format!(
+        "Post-install validation failed: {}",
+        details.join("; ")
+    ));
+    result.success = false;
+    result.message = format!("{} {}", result.message, error);
+    result.error_code.get_or_insert(error.code());
+    if result.recovery_hint.is_none() {
+        result.recovery_hint = error::suggest_recovery(&error);
+    }
+    result.errors.extend(details);
+}
+
+/// The checks that can stop an install before anything is written, and the upgrade and
+/// downgrade handling. `Ok` carries the downgrade warning, if any; `Err` is a result to
+/// return as-is, e.g. a failed check or the repair that performs an upgrade.
+fn prepare_install() -> Result<Option<String>, Box<PatchResult>> {
+    let current = settings::load_settings();
+    if settings::assert_enabled(&current).is_err() {
+        return Err(Box::new(PatchResult::disabled()));
+    }
+    if let Err(e) = check_install_scope(&current) {
+        return Err(Box::new(PatchResult::failed(&e, vec![])));
+    }
+    if let Err(e) = storage::assert_writable() {
+        return Err(Box::new(PatchResult::failed(&e, vec![])));
+    }
+
+    let checks = prerequisites::check_prerequisites();
+    if let Some(check) = prerequisites::first_blocking(&checks) {
+        let error = UprootedError::PrerequisiteFailed(format!("{}. {}", check.name, check.message));
+        return Err(Box::new(PatchResult::failed(&error, vec![])));
+    }
+
+    // Upgrades keep settings and existing backups; repair redeploys over them.
+    match detection::detect_install_state() {
+        InstallState::InstalledOlderVersion(from) => {
+            tracing::info!("upgrading from {}", from);
+            Err(Box::new(run_repair()))
+        }
+        InstallState::InstalledNewerVersion(from) => {
+            tracing::warn!("downgrading from {}", from);
+            Ok(Some(format!(
+                "Downgraded from {}; settings saved by the newer version may not be compatible.",
+                from
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Patch HTML files (or strip them in env-only mode) and check what was written.
+fn patch_html(continue_on_error: bool) -> PatchResult {
+    let mut result = if html_patching_disabled() {
+        env_only_result("installed")
+    } else {
+        patcher::install(continue_on_error)
+    };
+    validate_patched_files(&mut result);
+    result
+}
+
+/// Record a finished install: the receipt, the first-run flag and any downgrade warning.
+fn finish_install(
+    result: &mut PatchResult,
+    continue_on_error: bool,
+    downgrade_warning: Option<String>,
+) {
+    if result.success || (continue_on_error && !result.files_patched.is_empty()) {
+        if let Err(e) = receipt::write_install_receipt(&result.files_patched) {
+            tracing::warn!("{}", e);
+        }
+    }
+    if result.success {
+        mark_first_run_seen();
+    }
+    if let Some(warning) = downgrade_warning {
+        result.message = format!("{} {}", result.message, warning);
+    }
+}
+
+fn run_install(continue_on_error: bool) -> PatchResult {
+    let downgrade_warning = match prepare_install() {
+        Ok(warning) => warning,
+        Err(result) => return *result,
+    };
+
     // Step 1: Deploy embedded files
     if let Err(e) = hook::deploy_files() {
         return PatchResult {
             success: false,
             message: format!("Failed to deploy files: {}", e),
             files_patched: vec![],
+            recovery_hint: error::suggest_recovery(&e),
+            error_code: Some(e.code()),
+            size_warning: None,
+            errors: vec![e.to_string()],
+            warnings: vec![],
+            csp_warning: None,
         };
     }
 
     // Step 2: Set environment variables
     if let Err(e) = hook::set_env_vars() {
-        return PatchResult {
-            success: false,
-            message: format!("Failed to set env vars: {}", e),
-            files_patched: vec![],
-        };
+        let error = error::env_var_error(format!("Failed to set env vars: {}", e));
+        return PatchResult::failed(&error, vec![]);
     }
 
     // Step 3: Patch HTML files
-    patcher::install()
+    let mut result = patch_html(continue_on_error);
+    finish_install(&mut result, continue_on_error, downgrade_warning);
+    result
+}
+
+/// Reply channel for the staged install step that is waiting on the user.
+#[derive(Default)]
+struct StageConfirm(Mutex<Option<Sender<bool>>>);
+
+impl StageConfirm {
+    fn set(&self, tx: Sender<bool>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(tx);
+    }
+
+    fn take(&self) -> Option<Sender<bool>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct StageReady {
+    stage: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct StageConfirmPayload {
+    proceed: bool,
+}
+
+#[derive(Serialize)]
+struct StageResult {
+    success: bool,
+    message: String,
+    stages_completed: Vec<String>,
+    /// The stage the user declined, if any. Earlier stages were rolled back.
+    cancelled_at: Option<String>,
+    /// The patch stage's result, or the whole result when the install ran unstaged
+    /// (an upgrade, or a check that failed).
+    patch_result: Option<PatchResult>,
+}
+
+/// How long a stage waits for the user before it counts as declined.
+const STAGE_CONFIRM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+const INSTALL_STAGES: [(&str, &str); 3] = [
+    ("deploy", "Copy the profiler, hook and preload files to the uprooted directory."),
+    ("env", "Set the environment variables that load the hook into Root."),
+    ("patch", "Patch Root's HTML files to load uprooted."),
+];
+
+/// Announce `stage` with `uprooted://stage-ready` and block until the frontend
+/// answers with `uprooted://stage-confirm`. No answer within `STAGE_CONFIRM_TIMEOUT`,
+/// or the window closing (which drops the sender), counts as a no.
+fn confirm_stage(
+    app: &tauri::AppHandle,
+    window: &tauri::Window,
+    stage: &str,
+    description: &str,
+) -> bool {
+    let (tx, rx) = bounded(1);
+    app.state::<StageConfirm>().set(tx);
+    let _ = window.emit(
+        "uprooted://stage-ready",
+        StageReady {
+            stage: stage.to_string(),
+            description: description.to_string(),
+        },
+    );
+    let proceed = rx.recv_timeout(STAGE_CONFIRM_TIMEOUT).unwrap_or(false);
+    app.state::<StageConfirm>().take();
+    proceed
+}
+
+fn run_install_staged(app: &tauri::AppHandle, window: &tauri::Window) -> StageResult {
+    let mut result = StageResult {
+        success: false,
+        message: String::new(),
+        stages_completed: vec![],
+        cancelled_at: None,
+        patch_result: None,
+    };
+    let downgrade_warning = match prepare_install() {
+        Ok(warning) => warning,
+        Err(unstaged) => {
+            result.success = unstaged.success;
+            result.message = unstaged.message.clone();
+            result.patch_result = Some(*unstaged);
+            return result;
+        }
+    };
+
+    // A re-install over a working one is rolled back to it, not removed.
+    let snapshot = InstallSnapshot {
+        hook: hook::HookSnapshot::take(),
+        html: patcher::HtmlSnapshot::take(),
+    };
+
+    for (stage, description) in INSTALL_STAGES {
+        if !confirm_stage(app, window, stage, description) {
+            rollback_stages(&result.stages_completed, &snapshot);
+            result.message = format!("Install cancelled before the {} stage.", stage);
+            result.cancelled_at = Some(stage.to_string());
+            return result;
+        }
+
+        let outcome = match stage {
+            "deploy" => hook::deploy_files().map_err(|e| format!("Failed to deploy files: {}", e)),
+            "env" => hook::set_env_vars().map_err(|e| format!("Failed to set env vars: {}", e)),
+            _ => {
+                let patch = patch_html(false);
+                let outcome = if patch.success { Ok(()) } else { Err(patch.message.clone()) };
+                result.patch_result = Some(patch);
+                outcome
+            }
+        };
+        result.stages_completed.push(stage.to_string());
+        if let Err(e) = outcome {
+            // The failed stage may have partly applied, so it is undone too.
+            rollback_stages(&result.stages_completed, &snapshot);
+            result.stages_completed.clear();
+            result.message = e;
+            return result;
+        }
+    }
+
+    if let Some(patch) = result.patch_result.as_mut() {
+        finish_install(patch, false, downgrade_warning);
+        result.message = patch.message.clone();
+    }
+    result.success = true;
+    result
 }
 
+/// What the staged stages may change, taken before the first one runs.
+struct InstallSnapshot {
+    hook: hook::HookSnapshot,
+    html: patcher::HtmlSnapshot,
+}
+
+/// Undo the stages that ran before the user cancelled or a stage failed, newest first,
+/// by restoring what they changed from `snapshot`.
+fn rollback_stages(completed: &[String], snapshot: &InstallSnapshot) {
+    for stage in completed.iter().rev() {
+        let undone = match stage.as_str() {
+            "deploy" => snapshot.hook.restore_files(),
+            "env" => snapshot.hook.restore_env(),
+            _ => snapshot.html.restore(),
+        };
+        if let Err(e) = undone {
+            tracing::warn!("Rolling back {} failed: {}", stage, e);
+        }
+    }
+}
+
+/// Install one stage at a time, waiting for the user to confirm each. `install_uprooted`
+/// stays unattended.
 #[tauri::command]
-fn uninstall_uprooted() -> PatchResult {
-    // Step 1: Remove environment variables
+async fn install_staged(
+    app: tauri::AppHandle,
+    window: tauri::Window,
+) -> Result<StageResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let operation = app.state::<CurrentOperation>();
+        let _operation = operation.begin("Installing...").map_err(|e| e.to_string())?;
+        Ok(run_install_staged(&app, &window))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn mark_first_run_seen() {
+    let mut s = settings::load_settings();
+    if !s.first_run_seen {
+        s.first_run_seen = true;
+        if let Err(e) = settings::save_settings(&s) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+/// True until the first install succeeds; the UI shows its welcome screen meanwhile.
+#[tauri::command]
+fn is_first_run() -> bool {
+    !settings::load_settings().first_run_seen
+}
+
+fn html_patching_disabled() -> bool {
+    settings::load_settings().hook_mode == settings::HookMode::EnvOnlyNoHtml
+}
+
+/// Result for the env-only hook mode, where HTML patching is skipped on purpose.
+/// An injection left by an earlier mode is stripped so the mode switch takes effect.
+fn env_only_result(action: &str) -> PatchResult {
+    let stripped = patcher::uninstall();
+    if !stripped.success {
+        return stripped;
+    }
+    let removed = match stripped.files_patched.len() {
+        0 => String::new(),
+        n => format!(" Removed the existing injection from {} files.", n),
+    };
+    PatchResult {
+        success: true,
+        message: format!(
+            "Uprooted {}. HTML patching is disabled in env-only mode.{}",
+            action, removed
+        ),
+        files_patched: vec![],
+        recovery_hint: None,
+        error_code: None,
+        size_warning: None,
+        errors: vec![],
+        warnings: vec![],
+        csp_warning: None,
+    }
+}
+
+#[tauri::command]
+fn detect_legacy_install() -> legacy::LegacyInstallInfo {
+    legacy::detect_legacy_install()
+}
+
+#[tauri::command]
+fn migrate_from_legacy(operation: tauri::State<CurrentOperation>) -> Result<(), String> {
+    let _operation = operation.begin("Migrating...").map_err(|e| e.to_string())?;
+    legacy::clean_legacy_install()?;
+    let result = run_install(false);
+    if result.success {
+        Ok(())
+    } else {
+        Err(result.message)
+    }
+}
+
+#[tauri::command]
+fn get_install_state() -> InstallState {
+    detection::detect_install_state()
+}
+
+#[tauri::command]
+fn patch_file(path: String, operation: tauri::State<CurrentOperation>) -> PatchResult {
+    let _operation = match operation.begin("Patching...") {
+        Ok(guard) => guard,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    patcher::patch_file(&path)
+}
+
+#[tauri::command]
+fn unpatch_file(path: String, operation: tauri::State<CurrentOperation>) -> PatchResult {
+    let _operation = match operation.begin("Restoring...") {
+        Ok(guard) => guard,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    patcher::unpatch_file(&path)
+}
+
+#[tauri::command]
+fn uninstall_uprooted(operation: tauri::State<CurrentOperation>) -> PatchResult {
+    let _operation = match operation.begin("Uninstalling...") {
+        Ok(guard) => guard,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    run_uninstall()
+}
+
+fn run_uninstall() -> PatchResult {
+    if let Err(e) = storage::assert_writable() {
+        return PatchResult::failed(&e, vec![]);
+    }
+
+    // Step 1: Remove the monitors first so they can't redeploy mid-uninstall or at next login
+    let monitor_warnings: Vec<String> = [
+        service::remove_scheduled_task(),
+        service::uninstall_systemd_service(),
+        autostart::remove_autostart(),
+    ]
+    .into_iter()
+    .filter_map(Result::err)
+    .collect();
+    for warning in &monitor_warnings {
+        tracing::warn!("uninstall: {}", warning);
+    }
+
+    // Step 2: Remove environment variables
     if let Err(e) = hook::remove_env_vars() {
-        return PatchResult {
-            success: false,
-            message: format!("Failed to remove env vars: {}", e),
-            files_patched: vec![],
-        };
+        let error = error::env_var_error(format!("Failed to remove env vars: {}", e));
+        return PatchResult::failed(&error, vec![]);
     }
 
-    // Step 2: Restore HTML files
-    let result = patcher::uninstall();
+    // Step 3: Restore HTML files
+    let mut result = patcher::uninstall();
+    receipt::remove_receipt();
 
-    // Step 3: Remove deployed files
+    // Step 4: Remove deployed files
     if let Err(e) = hook::remove_files() {
-        return PatchResult {
-            success: false,
-            message: format!("HTML restored but failed to remove files: {}", e),
-            files_patched: result.files_patched,
-        };
+        let error = UprootedError::Io(format!("HTML restored but failed to remove files: {}", e));
+        return PatchResult::failed(&error, result.files_patched);
     }
 
+    result.warnings.extend(monitor_warnings);
+    result
+}
+
+#[tauri::command]
+fn purge_all_uprooted_data(
+    operation: tauri::State<CurrentOperation>,
+) -> Result<storage::PurgeReport, String> {
+    if hook::check_root_running() {
+        return Err("Close Root before purging Uprooted data.".to_string());
+    }
+
+    let _operation = operation.begin("Purging...").map_err(|e| e.to_string())?;
+    let mut report = storage::PurgeReport::default();
+    let before = storage::purgeable_bytes();
+
+    let result = run_uninstall();
+    if result.success {
+        report.steps_completed.push("Uninstalled Uprooted".to_string());
+    } else {
+        report.errors.push(result.message);
+    }
+
+    storage::purge_leftovers(&mut report);
+    report.bytes_freed = before.saturating_sub(storage::purgeable_bytes());
+    tracing::info!(
+        "purge: {} steps, {} errors, {} bytes freed",
+        report.steps_completed.len(),
+        report.errors.len(),
+        report.bytes_freed
+    );
+    Ok(report)
+}
+
+/// Set when a settings change couldn't be applied because Root was running.
+#[derive(Default)]
+struct PendingRepair(AtomicBool);
+
+#[tauri::command]
+fn repair_uprooted(
+    app: tauri::AppHandle,
+    pending: tauri::State<PendingRepair>,
+    operation: tauri::State<CurrentOperation>,
+) -> PatchResult {
+    let _operation = match operation.begin("Repairing...") {
+        Ok(guard) => guard,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    let result = with_upgrade_event(&app, run_repair);
+    if result.success {
+        pending.0.store(false, Ordering::SeqCst);
+    }
     result
 }
 
 #[tauri::command]
-fn repair_uprooted() -> PatchResult {
+fn get_pending_repair(pending: tauri::State<PendingRepair>) -> bool {
+    pending.0.load(Ordering::SeqCst)
+}
+
+fn run_repair() -> PatchResult {
+    if settings::assert_enabled(&settings::load_settings()).is_err() {
+        return PatchResult::disabled();
+    }
+    if let Err(e) = storage::assert_writable() {
+        return PatchResult::failed(&e, vec![]);
+    }
+
     // Re-deploy files (overwrite)
     if let Err(e) = hook::deploy_files() {
         return PatchResult {
             success: false,
             message: format!("Failed to deploy files: {}", e),
             files_patched: vec![],
+            recovery_hint: error::suggest_recovery(&e),
+            error_code: Some(e.code()),
+            size_warning: None,
+            errors: vec![e.to_string()],
+            warnings: vec![],
+            csp_warning: None,
         };
     }
 
     // Re-set env vars
     if let Err(e) = hook::set_env_vars() {
-        return PatchResult {
-            success: false,
-            message: format!("Failed to set env vars: {}", e),
-            files_patched: vec![],
-        };
+        let error = error::env_var_error(format!("Failed to set env vars: {}", e));
+        return PatchResult::failed(&error, vec![]);
     }
 
     // Re-patch HTML
-    patcher::repair()
+    let result = if html_patching_disabled() {
+        env_only_result("repaired")
+    } else {
+        patcher::repair()
+    };
+    if result.success {
+        if let Err(e) = receipt::record_repair(&result.files_patched) {
+            tracing::warn!("{}", e);
+        }
+    }
+    result
+}
+
+// Network commands run on the blocking pool: reqwest's blocking client can't be
+// used from the async runtime, and sync commands would stall the main thread.
+#[tauri::command]
+async fn check_for_artifact_updates() -> Result<Vec<ArtifactUpdate>, String> {
+    tauri::async_runtime::spawn_blocking(updates::check_for_artifact_updates)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn apply_artifact_updates(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        updates::apply_artifact_updates(|progress| {
+            let _ = app.emit("uprooted://download-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -113,36 +888,266 @@ fn load_settings() -> UprootedSettings {
     settings::load_settings()
 }
 
+#[tauri::command]
+fn get_settings_diff() -> settings::SettingsDiff {
+    settings::get_settings_diff()
+}
+
+#[tauri::command]
+fn get_settings_override_path() -> Option<String> {
+    settings::settings_override_path().map(|p| p.to_string_lossy().to_string())
+}
+
+/// `UPROOTED_SETTINGS_PATH` if it names a file that doesn't exist, so the page can
+/// say why default settings are in use.
+#[tauri::command]
+fn get_missing_settings_override() -> Option<String> {
+    settings::settings_override_path()
+        .filter(|p| !p.exists())
+        .map(|p| p.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 fn save_settings(settings: UprootedSettings) -> Result<(), String> {
+    plugins::validate_changed_plugin_configs(&settings::load_settings(), &settings)?;
     settings::save_settings(&settings)
 }
 
+#[tauri::command]
+fn save_settings_field(field_path: String, value: serde_json::Value) -> Result<(), String> {
+    settings::save_settings_field(&field_path, value)
+}
+
+#[tauri::command]
+fn set_desktop_file_config(
+    config: settings::DesktopFileConfig,
+    operation: tauri::State<CurrentOperation>,
+) -> Result<(), String> {
+    let _operation = operation.begin("Updating the desktop entry...").map_err(|e| e.to_string())?;
+    hook::set_desktop_file_config(config)
+}
+
+#[tauri::command]
+fn validate_profiler_guid(guid: String) -> Result<(), String> {
+    settings::validate_profiler_guid(&guid)
+}
+
+#[tauri::command]
+fn validate_custom_css(css: String) -> Result<(), Vec<CssError>> {
+    css::validate_css(&css)
+}
+
+/// Profiling aid for the deploy-time CSS minifier. Not compiled into release builds.
+#[cfg(debug_assertions)]
+#[tauri::command]
+fn benchmark_css_minification() -> Result<css::BenchmarkResult, String> {
+    use uprooted_installer::embedded;
+
+    let raw = embedded::decompress(embedded::THEME_CSS).map_err(|e| e.to_string())?;
+    let source = std::str::from_utf8(&raw).map_err(|e| e.to_string())?;
+    css::benchmark_minification(source, 50)
+}
+
+#[tauri::command]
+fn list_available_plugins() -> Vec<PluginInfo> {
+    plugins::list_available_plugins()
+}
+
+#[tauri::command]
+fn get_plugin_config_schema(plugin_name: String) -> Option<serde_json::Value> {
+    plugins::get_config_schema(&plugin_name)
+}
+
 #[tauri::command]
 fn list_themes() -> Vec<ThemeDefinition> {
-    themes::get_builtin_themes()
+    themes::get_all_themes()
+}
+
+#[tauri::command]
+fn compare_themes(name_a: String, name_b: String) -> Result<themes::ThemeDiff, String> {
+    themes::compare_themes(&name_a, &name_b)
 }
 
 #[tauri::command]
-fn apply_theme(name: String) -> Result<(), String> {
+fn filter_themes(query: themes::ThemeQuery) -> Vec<ThemeDefinition> {
+    themes::filter_themes(&query)
+}
+
+#[tauri::command]
+fn import_theme(json: String) -> Result<themes::ImportResult, String> {
+    themes::import_theme(&json)
+}
+
+#[tauri::command]
+async fn import_theme_from_url(url: String, window: tauri::Window) -> Result<ThemeDefinition, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        themes::import_theme_from_url(&url, |received, total| {
+            let _ = window.emit(
+                "uprooted://download-progress",
+                updates::DownloadProgress {
+                    name: url.clone(),
+                    bytes_received: received,
+                    total_bytes: total,
+                },
+            );
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn import_theme_zip(path: String) -> Result<themes::ImportResult, String> {
+    themes::import_theme_zip(std::path::Path::new(&path))
+}
+
+#[tauri::command]
+fn theme_asset_path(theme_name: String, asset: String) -> Result<String, String> {
+    themes::theme_asset_path(&theme_name, &asset)
+}
+
+#[tauri::command]
+fn validate_theme_contrast(theme: ThemeDefinition) -> Vec<themes::ContrastWarning> {
+    themes::validate_theme_contrast(&theme)
+}
+
+#[tauri::command]
+fn generate_theme_from_accent(
+    accent_hex: String,
+    base: themes::ThemeBase,
+) -> Result<ThemeDefinition, String> {
+    themes::generate_theme_from_accent(&accent_hex, base)
+}
+
+#[tauri::command]
+fn apply_theme(name: String, operation: tauri::State<CurrentOperation>) -> Result<(), String> {
+    let _operation = operation.begin("Applying theme...").map_err(|e| e.to_string())?;
+    if let Some(theme) = themes::get_all_themes().iter().find(|t| t.name == name) {
+        for w in themes::validate_theme_contrast(theme) {
+            tracing::warn!(
+                "theme {}: {}/{} contrast {}:1 below {}:1",
+                name, w.pair.0, w.pair.1, w.ratio, w.required
+            );
+        }
+    }
     let mut s = settings::load_settings();
     let theme_settings = s.plugins.entry("themes".to_string()).or_insert_with(|| {
         settings::PluginSettings {
             enabled: true,
             config: std::collections::HashMap::new(),
+            priority: 0,
         }
     });
-    theme_settings
-        .config
-        .insert("theme".to_string(), serde_json::Value::String(name));
+    theme_settings.set_str("theme", &name);
+    plugins::validate_plugin_config(&s, "themes")?;
     settings::save_settings(&s)
 }
 
+#[tauri::command]
+fn reset_plugin_config(
+    plugin_name: String,
+    operation: tauri::State<CurrentOperation>,
+) -> Result<(), String> {
+    let _operation = operation.begin("Resetting plugin...").map_err(|e| e.to_string())?;
+    let plugin = plugins::list_available_plugins()
+        .into_iter()
+        .find(|p| p.manifest.name == plugin_name)
+        .ok_or_else(|| format!("Plugin '{}' is not installed.", plugin_name))?;
+    let mut s = settings::load_settings();
+    plugins::reset_config(&mut s, &plugin.manifest);
+    plugins::validate_plugin_config(&s, &plugin_name)?;
+    settings::save_settings(&s)
+}
+
+#[tauri::command]
+fn reset_all_plugins(operation: tauri::State<CurrentOperation>) -> Result<(), String> {
+    let _operation = operation.begin("Resetting plugins...").map_err(|e| e.to_string())?;
+    let mut s = settings::load_settings();
+    for plugin in plugins::list_available_plugins() {
+        plugins::reset_config(&mut s, &plugin.manifest);
+        plugins::validate_plugin_config(&s, &plugin.manifest.name)?;
+    }
+    settings::save_settings(&s)
+}
+
+/// Enable or disable a plugin, then redeploy so Root picks it up. If Root is
+/// running the repair is deferred and the UI is told via `uprooted://pending-repair`.
+#[tauri::command]
+fn toggle_plugin(
+    app: tauri::AppHandle,
+    pending: tauri::State<PendingRepair>,
+    operation: tauri::State<CurrentOperation>,
+    name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let _operation = operation.begin("Updating plugins...").map_err(|e| e.to_string())?;
+    let mut s = settings::load_settings();
+    s.plugins
+        .entry(name.clone())
+        .or_insert_with(|| settings::PluginSettings {
+            enabled,
+            config: std::collections::HashMap::new(),
+            priority: 0,
+        })
+        .enabled = enabled;
+    if enabled {
+        plugins::validate_plugin_config(&s, &name)?;
+    }
+    settings::save_settings(&s)?;
+
+    if detection::detect_install_state() == InstallState::NotInstalled {
+        return Ok(());
+    }
+    if hook::check_root_running() {
+        pending.0.store(true, Ordering::SeqCst);
+        let _ = app.emit("uprooted://pending-repair", true);
+        return Ok(());
+    }
+
+    let result = run_repair();
+    if result.success {
+        pending.0.store(false, Ordering::SeqCst);
+        Ok(())
+    } else {
+        Err(result.message)
+    }
+}
+
 #[tauri::command]
 fn get_uprooted_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+#[tauri::command]
+fn get_artifact_versions() -> artifact_meta::ArtifactVersions {
+    artifact_meta::get_artifact_versions()
+}
+
+#[tauri::command]
+fn get_artifact_size(name: String) -> Option<usize> {
+    hook::artifact_size(&name)
+}
+
+#[tauri::command]
+fn get_disk_usage() -> storage::DiskUsage {
+    storage::get_disk_usage()
+}
+
+#[tauri::command]
+fn get_changelog() -> String {
+    embedded_changelog::CHANGELOG.to_string()
+}
+
+#[tauri::command]
+fn get_version_changelog(version: String) -> Option<String> {
+    embedded_changelog::version_section(&version)
+}
+
+#[tauri::command]
+fn parse_log_entries(limit: u32) -> Vec<logging::LogEntry> {
+    logging::parse_log_entries(limit)
+}
+
 #[tauri::command]
 fn open_profile_dir() -> Result<(), String> {
     let profile = detection::get_profile_dir();
@@ -153,7 +1158,172 @@ fn open_profile_dir() -> Result<(), String> {
     }
 }
 
+#[tauri::command]
+fn open_uprooted_dir() -> Result<(), String> {
+    let dir = hook::get_uprooted_dir();
+    if dir.exists() {
+        opener::open(dir).map_err(|e| e.to_string())
+    } else {
+        Err("Uprooted is not installed yet, so its folder does not exist.".to_string())
+    }
+}
+
+#[tauri::command]
+fn open_settings_dir() -> Result<(), String> {
+    let path = settings::settings_path();
+    let dir = path
+        .parent()
+        .ok_or_else(|| "Settings path has no parent directory.".to_string())?;
+    if dir.exists() {
+        opener::open(dir).map_err(|e| e.to_string())
+    } else {
+        Err("Profile directory does not exist.".to_string())
+    }
+}
+
+#[tauri::command]
+fn install_systemd_service() -> Result<(), String> {
+    service::install_systemd_service()
+}
+
+#[tauri::command]
+fn uninstall_systemd_service() -> Result<(), String> {
+    service::uninstall_systemd_service()
+}
+
+#[tauri::command]
+fn get_service_status() -> service::ServiceStatus {
+    service::get_service_status()
+}
+
+#[tauri::command]
+fn install_scheduled_task() -> Result<(), String> {
+    service::install_scheduled_task()
+}
+
+#[tauri::command]
+fn remove_scheduled_task() -> Result<(), String> {
+    service::remove_scheduled_task()
+}
+
+#[tauri::command]
+fn is_scheduled_task_installed() -> bool {
+    service::is_scheduled_task_installed()
+}
+
+#[tauri::command]
+fn install_autostart() -> Result<(), String> {
+    autostart::install_autostart()
+}
+
+#[tauri::command]
+fn remove_autostart() -> Result<(), String> {
+    autostart::remove_autostart()
+}
+
+#[tauri::command]
+fn is_autostart_enabled() -> bool {
+    autostart::is_autostart_enabled()
+}
+
+#[derive(Serialize, Clone)]
+struct AutoRepairEvent {
+    success: bool,
+    message: String,
+}
+
+/// What `startup_auto_repair()` did before the window opened. Kept until the page
+/// asks for it, since an event sent then would arrive before any listener exists.
+#[derive(Default)]
+struct StartupRepair(Mutex<Option<AutoRepairEvent>>);
+
+/// The startup auto-repair result, once; `None` if nothing was repaired.
+#[tauri::command]
+fn get_startup_repair_result(repair: tauri::State<StartupRepair>) -> Option<AutoRepairEvent> {
+    repair.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Redeploy files if they were deleted while the env vars still point at them, and
+/// re-point CORECLR_PROFILER_PATH if it names a profiler somewhere else. Either
+/// would otherwise make Root fail on its next launch.
+fn startup_auto_repair() -> Option<AutoRepairEvent> {
+    let settings = settings::load_settings();
+    if !settings.startup_check || settings::assert_enabled(&settings).is_err() {
+        return None;
+    }
+    let status = hook::check_hook_status();
+    let stale_path = status.env_profiler_path && !status.env_profiler_path_valid;
+    if !stale_path && (status.files_ok || !status.env_ok) {
+        return None;
+    }
+
+    let event = if stale_path {
+        tracing::info!("CORECLR_PROFILER_PATH points elsewhere; redeploying and resetting it");
+        match hook::deploy_files()
+            .map_err(|e| e.to_string())
+            .and_then(|()| hook::set_env_vars())
+        {
+            Ok(()) => AutoRepairEvent {
+                success: true,
+                message: "The profiler path was reset to the deployed profiler.".to_string(),
+            },
+            Err(e) => AutoRepairEvent {
+                success: false,
+                message: format!("Failed to reset the profiler path: {}", e),
+            },
+        }
+    } else {
+        tracing::info!("Deployed files are missing but env vars are set; redeploying");
+        match hook::deploy_files() {
+            Ok(()) => AutoRepairEvent {
+                success: true,
+                message: "Missing uprooted files were redeployed.".to_string(),
+            },
+            Err(e) => AutoRepairEvent {
+                success: false,
+                message: format!("Failed to deploy files: {}", e),
+            },
+        }
+    };
+    tracing::info!("Auto-repair finished: {}", event.message);
+    Some(event)
+}
+
 fn main() {
+    logging::init();
+
+    if let Some((request_path, result_path)) = elevation::elevated_args() {
+        elevation::handle_elevated_request(&request_path, &result_path);
+        hook::wait_for_env_broadcasts();
+        return;
+    }
+
+    if std::env::args().any(|a| a == "--check-repair") {
+        // After an uninstall the files are gone on purpose; only repair a live install.
+        let status = hook::check_hook_status();
+        let installed = receipt::read_receipt().is_some()
+            || status.env_enable_profiling
+            || status.env_startup_hook;
+        if installed && !status.files_ok {
+            let result = run_repair();
+            tracing::info!("check-repair: {}", result.message);
+        }
+        hook::wait_for_env_broadcasts();
+        return;
+    }
+
+    if std::env::args().any(|a| a == "--daemon") {
+        daemon::start_process_monitor(
+            || {
+                startup_auto_repair();
+            },
+            || {},
+        );
+        return;
+    }
+
+    let auto_repair = startup_auto_repair();
+
     // WebKitGTK GPU compositing causes blank/white windows on many Wayland compositors
     // (KDE Plasma, GNOME, Fedora, etc). Disable before WebKit initializes.
     if is_wayland_session() {
@@ -163,25 +1333,113 @@ fn main() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(DetectionCache::default())
+        .manage(PendingRepair::default())
+        .manage(StageConfirm::default())
+        .manage(CurrentOperation::default())
+        .manage(HookStatusPoll::default())
+        .manage(StartupRepair(Mutex::new(auto_repair)))
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                // Unblocks a staged install waiting on this window's answer.
+                window.state::<StageConfirm>().take();
+                window.state::<HookStatusPoll>().stop();
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             detect_root,
+            invalidate_detection_cache,
+            list_target_files,
+            list_root_apps,
+            preview_injection_string,
+            dump_injection_to_file,
+            open_injection_debug,
+            scan_for_foreign_injections,
+            check_prerequisites,
+            get_system_info,
+            generate_install_docs,
             check_hook_status,
+            get_active_hooks,
+            start_hook_status_poll,
+            stop_hook_status_poll,
+            get_hook_versions,
+            get_health_score,
+            list_env_vars,
+            get_hook_severity,
+            verify_deployed_integrity,
             check_root_running,
+            is_read_only,
             kill_root,
+            relaunch_via_wrapper,
             install_uprooted,
+            get_current_operation,
+            install_uprooted_tolerant,
+            install_staged,
+            is_first_run,
+            get_install_state,
+            detect_legacy_install,
+            migrate_from_legacy,
+            patch_file,
+            unpatch_file,
             uninstall_uprooted,
             repair_uprooted,
+            repair_file,
+            get_pending_repair,
+            purge_all_uprooted_data,
+            check_for_artifact_updates,
+            apply_artifact_updates,
             load_settings,
             save_settings,
+            get_settings_override_path,
+            get_missing_settings_override,
+            get_startup_repair_result,
+            save_settings_field,
+            get_settings_diff,
+            validate_profiler_guid,
+            set_desktop_file_config,
+            validate_custom_css,
+            #[cfg(debug_assertions)]
+            benchmark_css_minification,
+            list_available_plugins,
+            get_plugin_config_schema,
             list_themes,
+            filter_themes,
+            compare_themes,
             apply_theme,
+            generate_theme_from_accent,
+            import_theme,
+            import_theme_zip,
+            import_theme_from_url,
+            theme_asset_path,
+            validate_theme_contrast,
+            toggle_plugin,
+            reset_plugin_config,
+            reset_all_plugins,
             get_uprooted_version,
+            get_disk_usage,
+            get_artifact_size,
+            get_artifact_versions,
+            get_changelog,
+            get_version_changelog,
+            parse_log_entries,
             open_profile_dir,
+            open_uprooted_dir,
+            open_settings_dir,
+            install_systemd_service,
+            uninstall_systemd_service,
+            get_service_status,
+            install_scheduled_task,
+            remove_scheduled_task,
+            is_scheduled_task_installed,
+            install_autostart,
+            remove_autostart,
+            is_autostart_enabled,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             let use_transparency = !is_wayland_session();
 
-            tauri::WebviewWindowBuilder::new(
+            let window = tauri::WebviewWindowBuilder::new(
                 app,
                 "main",
                 tauri::WebviewUrl::App("index.html".into()),
@@ -194,6 +1452,49 @@ fn main() {
             .center()
             .build()?;
 
+            // load_settings() falls back to defaults without a word, so say why.
+            if let Some(path) = settings::settings_override_path().filter(|p| !p.exists()) {
+                tracing::warn!(
+                    "UPROOTED_SETTINGS_PATH points to {}, which does not exist; using default settings",
+                    path.display()
+                );
+            }
+
+            let handle = app.handle().clone();
+            app.listen_any("uprooted://stage-confirm", move |event| {
+                let proceed = serde_json::from_str::<StageConfirmPayload>(event.payload())
+                    .map(|p| p.proceed)
+                    .unwrap_or(false);
+                if let Some(tx) = handle.state::<StageConfirm>().take() {
+                    let _ = tx.send(proceed);
+                }
+            });
+
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                daemon::start_process_monitor(
+                    || {},
+                    move || {
+                        let _ = handle
+                            .notification()
+                            .builder()
+                            .title("uprooted is not active")
+                            .body("Root was started without uprooted. Relaunch it from the installer to load mods.")
+                            .show();
+                        let _ = window.emit("uprooted://hook-inactive", ());
+                    },
+                )
+            });
+
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                if let Ok(updates) = updates::check_for_artifact_updates() {
+                    if !updates.is_empty() {
+                        let _ = handle.emit("uprooted://artifact-updates-available", updates);
+                    }
+                }
+            });
+
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -1,21 +1,87 @@
-use crate::detection::find_target_html_files;
+use crate::artifact_meta;
+use crate::detection::{find_target_html_files, get_profile_dir};
+use crate::encoding::{self, HtmlEncoding};
+use crate::error::{suggest_recovery, UprootedError};
 use crate::hook;
-use crate::settings::load_settings;
-use serde::Serialize;
+use crate::plugins;
+use crate::settings::{assert_enabled, load_settings, save_settings, BackupNaming};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const MARKER_START: &str = "<!-- uprooted:start -->";
 const MARKER_END: &str = "<!-- uprooted:end -->";
+/// Records which preload.js version the injection block was written for.
+const VERSION_MARKER_PREFIX: &str = "<!-- uprooted:version ";
 /// Legacy marker for detection of older installs
 const LEGACY_MARKER: &str = "<!-- uprooted -->";
 const BACKUP_SUFFIX: &str = ".uprooted.bak";
+/// Inline settings above this size slow down every Root page load.
+const MAX_SETTINGS_INJECT_BYTES: usize = 65536;
+/// Where `custom_css` goes when it is too large to inline.
+const CUSTOM_CSS_FILE: &str = "uprooted-custom.css";
+/// Files with fewer line breaks than this are treated as minified.
+const MINIFIED_MAX_NEWLINES: usize = 5;
+/// Records the page's original Content-Security-Policy when `fix_csp` widened it.
+const CSP_MARKER_PREFIX: &str = "<!-- uprooted:csp ";
+/// Directives that decide whether the injected `file://` script and stylesheets load.
+const CSP_DIRECTIVES: &[&str] = &["default-src", "script-src", "style-src"];
+/// Sources `fix_csp` adds to each of `CSP_DIRECTIVES` that lacks `file:`.
+const CSP_SOURCES: &[&str] = &["file:", "blob:", "data:"];
+/// Standalone page in the uprooted directory showing the injection, for debugging.
+const INJECTION_DEBUG_FILE: &str = "debug_injection.html";
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct PatchResult {
     pub success: bool,
     pub message: String,
     pub files_patched: Vec<String>,
+    /// What the user can do about a failure, shown separately from `message`.
+    pub recovery_hint: Option<String>,
+    /// Stable code from `error.rs` for failures, so reports can be matched across versions.
+    pub error_code: Option<u32>,
+    /// Set when the settings were too large to inline in full.
+    pub size_warning: Option<String>,
+    /// Every per-file failure, when the operation kept going past the first one.
+    pub errors: Vec<String>,
+    /// Files skipped on purpose, e.g. because another framework already patched them.
+    pub warnings: Vec<String>,
+    /// Set when a patched page's Content-Security-Policy blocks `file:` URIs and
+    /// `fix_csp` is off, so the injection may not load.
+    pub csp_warning: Option<String>,
+}
+
+impl PatchResult {
+    pub fn failed(error: &UprootedError, files_patched: Vec<String>) -> Self {
+        Self {
+            success: false,
+            message: error.to_string(),
+            files_patched,
+            recovery_hint: suggest_recovery(error),
+            error_code: Some(error.code()),
+            size_warning: None,
+            errors: vec![error.to_string()],
+            warnings: vec![],
+            csp_warning: None,
+        }
+    }
+
+    /// Nothing was done because `enabled` is off in settings.
+    pub fn disabled() -> Self {
+        Self {
+            success: true,
+            message: UprootedError::Disabled.to_string(),
+            files_patched: vec![],
+            recovery_hint: None,
+            error_code: None,
+            size_warning: None,
+            errors: vec![],
+            warnings: vec![],
+            csp_warning: None,
+        }
+    }
 }
 
 /// Check whether a file contains any uprooted injection.
@@ -25,7 +91,205 @@ pub fn is_patched(content: &str) -> bool {
         || content.contains("uprooted-preload")
 }
 
-pub fn install() -> PatchResult {
+/// An injection written by the bash installer: bare tags or the old marker, no start/end block.
+pub fn is_legacy_injection(content: &str) -> bool {
+    !content.contains(MARKER_START)
+        && (content.contains(LEGACY_MARKER) || content.contains("uprooted-preload"))
+}
+
+/// Comment prefixes other Root modding frameworks leave around their injections.
+const FOREIGN_MARKERS: &[(&str, &str)] = &[
+    ("BetterChat", "<!-- betterchat:"),
+    ("RootMod", "<!-- rootmod:"),
+];
+
+#[derive(Serialize, Clone)]
+pub struct ForeignInjection {
+    pub framework_name: String,
+    pub marker_found: String,
+}
+
+#[derive(Serialize)]
+pub struct ForeignInjectionReport {
+    pub file: String,
+    pub injection: ForeignInjection,
+}
+
+/// Another modding framework's injection in `content`. Patching on top of it risks
+/// the two fighting over the page, so such files are left alone.
+pub fn detect_foreign_injection(content: &str) -> Option<ForeignInjection> {
+    let lower = content.to_lowercase();
+    FOREIGN_MARKERS
+        .iter()
+        .find(|(_, marker)| lower.contains(marker))
+        .map(|(name, marker)| ForeignInjection {
+            framework_name: name.to_string(),
+            marker_found: marker.to_string(),
+        })
+}
+
+/// Every target file that another framework has already patched.
+pub fn scan_for_foreign_injections() -> Vec<ForeignInjectionReport> {
+    find_target_html_files()
+        .iter()
+        .filter_map(|file| {
+            let (content, _) = encoding::detect_and_read_html(file).ok()?;
+            Some(ForeignInjectionReport {
+                file: file.to_string_lossy().to_string(),
+                injection: detect_foreign_injection(&content)?,
+            })
+        })
+        .collect()
+}
+
+/// Whether an injection whose version marker reads `version` (see [`injection_version`])
+/// was written for another preload.js version. A missing marker counts as outdated.
+pub fn is_injection_outdated(version: Option<&str>) -> bool {
+    version != Some(artifact_meta::PRELOAD_JS_VERSION)
+}
+
+/// Extract the preload.js version recorded in the injection block, if any.
+pub fn injection_version(content: &str) -> Option<String> {
+    let start = content.find(VERSION_MARKER_PREFIX)? + VERSION_MARKER_PREFIX.len();
+    let rest = &content[start..];
+    let end = rest.find("-->")?;
+    let version = rest[..end].trim();
+    if version.is_empty() {
+        None
+    } else {
+        Some(version.to_string())
+    }
+}
+
+/// Make serialized JSON safe to embed in an inline `<script>`: `</` can't close the
+/// tag and `<!--` can't open an HTML comment. Both escapes keep the JSON valid.
+pub fn json_escape_for_html(json: &str) -> String {
+    json.replace("<!--", "\\u003c!--").replace("</", "<\\/")
+}
+
+/// Path of the backup copy kept next to a patched file.
+pub fn backup_path_for(file: &Path) -> PathBuf {
+    PathBuf::from(format!("{}{}", file.to_string_lossy(), BACKUP_SUFFIX))
+}
+
+/// Where a new backup of `file` goes under the configured `backup_naming`.
+fn new_backup_path(file: &Path) -> PathBuf {
+    match load_settings().backup_naming {
+        BackupNaming::Simple => backup_path_for(file),
+        BackupNaming::Timestamped => PathBuf::from(format!(
+            "{}{}{}",
+            file.to_string_lossy(),
+            chrono::Local::now().format(".%Y%m%d_%H%M%S"),
+            BACKUP_SUFFIX
+        )),
+    }
+}
+
+/// `20250101_120000`, as written by `BackupNaming::Timestamped`.
+fn is_backup_timestamp(stamp: &str) -> bool {
+    stamp.len() == 15
+        && stamp.bytes().enumerate().all(|(i, b)| {
+            if i == 8 {
+                b == b'_'
+            } else {
+                b.is_ascii_digit()
+            }
+        })
+}
+
+/// Timestamped backups of `file`, oldest first. Timestamps sort by name, so no file
+/// times are needed.
+fn timestamped_backups_for(file: &Path) -> Vec<PathBuf> {
+    let Some(name) = file.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", name);
+    let mut backups: Vec<PathBuf> = file
+        .parent()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            let stamp = entry_name.strip_prefix(&prefix)?.strip_suffix(BACKUP_SUFFIX)?;
+            is_backup_timestamp(stamp).then(|| entry.path())
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+/// The backup to restore `file` from: the newest timestamped one, otherwise the
+/// simple `.uprooted.bak`.
+pub fn latest_backup_for(file: &Path) -> Option<PathBuf> {
+    timestamped_backups_for(file)
+        .pop()
+        .or_else(|| Some(backup_path_for(file)).filter(|p| p.exists()))
+}
+
+/// Every backup of `file`, under either naming.
+fn all_backups_for(file: &Path) -> Vec<PathBuf> {
+    let mut backups = timestamped_backups_for(file);
+    backups.extend(Some(backup_path_for(file)).filter(|p| p.exists()));
+    backups
+}
+
+/// Back up `file` as it is on disk, unless the newest backup already holds the same
+/// content (so timestamped backups don't pile up on every repair). With `replace`, a
+/// simple backup is overwritten instead of kept.
+fn backup_clean_file(file: &Path, replace: bool) -> Result<(), UprootedError> {
+    let failed = |e: std::io::Error| {
+        UprootedError::PatchFailed(format!("Failed to backup {}: {}", file.display(), e))
+    };
+    let current = fs::read(file).map_err(failed)?;
+    let unchanged = latest_backup_for(file)
+        .and_then(|backup| fs::read(backup).ok())
+        .is_some_and(|backup| backup == current);
+    let backup_path = new_backup_path(file);
+    if unchanged || (backup_path.exists() && !replace) {
+        return Ok(());
+    }
+    fs::write(&backup_path, current).map_err(failed)
+}
+
+struct Injection {
+    html: String,
+    size_warning: Option<String>,
+    /// Custom CSS moved out of the inline settings, to be written to `CUSTOM_CSS_FILE`.
+    custom_css: Option<String>,
+    /// Widen a restrictive Content-Security-Policy instead of only warning about it.
+    fix_csp: bool,
+}
+
+#[derive(Serialize)]
+pub struct InjectionPreview {
+    pub injection_html: String,
+    pub injection_bytes: usize,
+    pub target_files: Vec<String>,
+}
+
+/// Build the injection and write (or clean up) the custom CSS stylesheet it links to.
+fn build_injection() -> Result<Injection, UprootedError> {
+    let injection = render_injection();
+    let custom_css_path = hook::get_uprooted_dir().join(CUSTOM_CSS_FILE);
+    match &injection.custom_css {
+        Some(css) => fs::write(&custom_css_path, css).map_err(|e| {
+            UprootedError::PatchFailed(format!(
+                "Failed to write {}: {}",
+                custom_css_path.display(),
+                e
+            ))
+        })?,
+        None => {
+            let _ = fs::remove_file(&custom_css_path);
+        }
+    }
+    Ok(injection)
+}
+
+/// The block injected before `</head>`, from the current settings. Does not touch the disk.
+fn render_injection() -> Injection {
     let uprooted_dir = hook::get_uprooted_dir();
 
     let preload_path = uprooted_dir
@@ -37,123 +301,715 @@ pub fn install() -> PatchResult {
         .to_string_lossy()
         .replace('\\', "/");
 
-    let settings = load_settings();
-    let settings_json = serde_json::to_string(&settings).unwrap_or_else(|_| "{}".to_string());
+    let mut settings = load_settings();
+    let mut settings_json = serde_json::to_string(&settings).unwrap_or_else(|_| "{}".to_string());
+
+    // Oversized settings are almost always a pasted CSS framework in `custom_css`.
+    // Move it to a stylesheet on disk and keep the inline settings small.
+    let custom_css_path = uprooted_dir.join(CUSTOM_CSS_FILE);
+    let mut size_warning = None;
+    let mut custom_css = None;
+    if settings_json.len() > MAX_SETTINGS_INJECT_BYTES && !settings.custom_css.is_empty() {
+        size_warning = Some(format!(
+            "Settings are {} KB, over the {} KB inline limit. Custom CSS is loaded from {} instead.",
+            settings_json.len() / 1024,
+            MAX_SETTINGS_INJECT_BYTES / 1024,
+            CUSTOM_CSS_FILE
+        ));
+        custom_css = Some(std::mem::take(&mut settings.custom_css));
+        settings_json = serde_json::to_string(&settings).unwrap_or_else(|_| "{}".to_string());
+    }
+    let settings_json = json_escape_for_html(&settings_json);
 
     // On Linux, paths start with `/` so `file://` + `/home/...` = `file:///home/...` (correct).
     // On Windows, paths start with `C:\` so we need `file:///` to get `file:///C:/...`.
     let file_prefix = if cfg!(target_os = "windows") { "file:///" } else { "file://" };
 
-    let injection = format!(
-        "{start}\n    <script>window.__UPROOTED_SETTINGS__={settings};</script>\n    <script src=\"{prefix}{preload}\"></script>\n    <link rel=\"stylesheet\" href=\"{prefix}{css}\">\n    {end}",
+    let custom_css_link = if size_warning.is_some() {
+        format!(
+            "\n    <link rel=\"stylesheet\" href=\"{}{}\">",
+            file_prefix,
+            custom_css_path.to_string_lossy().replace('\\', "/")
+        )
+    } else {
+        String::new()
+    };
+
+    // Stamped when the file is patched, not at runtime, so plugins can tell which
+    // install produced the page they run in.
+    let meta_script = if settings.inject_timing {
+        let meta = serde_json::json!({
+            "version": artifact_meta::PRELOAD_JS_VERSION,
+            "injected_at": chrono::Utc::now().to_rfc3339(),
+        });
+        format!(
+            "\n    <script>window.__UPROOTED_META__={};</script>",
+            json_escape_for_html(&meta.to_string())
+        )
+    } else {
+        String::new()
+    };
+
+    let plugin_css_links: String = plugins::plugin_css_files(&settings)
+        .iter()
+        .map(|path| {
+            format!(
+                "\n    <link rel=\"stylesheet\" href=\"{}{}\">",
+                file_prefix,
+                path.to_string_lossy().replace('\\', "/")
+            )
+        })
+        .collect();
+
+    let builtin_theming_override = if settings.disable_root_builtin_theming {
+        "\n    <style>[data-theme] { all: unset; }</style>"
+    } else {
+        ""
+    };
+
+    let html = format!(
+        "{start}\n    {version_marker}{version} -->\n    <script>window.__UPROOTED_SETTINGS__={settings};</script>{meta}\n    <script src=\"{prefix}{preload}\"></script>\n    <link rel=\"stylesheet\" href=\"{prefix}{css}\">{plugin_css}{custom_css}{theming}\n    {end}",
         start = MARKER_START,
         end = MARKER_END,
+        version_marker = VERSION_MARKER_PREFIX,
+        version = artifact_meta::PRELOAD_JS_VERSION,
         settings = settings_json,
+        meta = meta_script,
         prefix = file_prefix,
         preload = preload_path,
         css = css_path,
+        plugin_css = plugin_css_links,
+        custom_css = custom_css_link,
+        theming = builtin_theming_override,
     );
 
-    let targets = find_target_html_files();
-    if targets.is_empty() {
-        return PatchResult {
-            success: false,
-            message: "No target HTML files found in profile directory.".to_string(),
-            files_patched: vec![],
-        };
+    Injection {
+        html,
+        size_warning,
+        custom_css,
+        fix_csp: settings.fix_csp,
     }
+}
 
-    let mut patched = Vec::new();
-    for file in &targets {
-        let content = match fs::read_to_string(file) {
-            Ok(c) => c,
-            Err(e) => {
-                return PatchResult {
-                    success: false,
-                    message: format!("Failed to read {}: {}", file.display(), e),
-                    files_patched: patched,
-                };
+/// Put each injected tag on its own line, indented two spaces inside the markers.
+pub fn pretty_print_html(html: &str) -> String {
+    html.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            if line == MARKER_START || line == MARKER_END {
+                line.to_string()
+            } else {
+                format!("  {}", line)
             }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What `install()` would inject, and where, without writing anything.
+pub fn preview_injection() -> InjectionPreview {
+    let injection = render_injection();
+    InjectionPreview {
+        injection_bytes: injection.html.len(),
+        injection_html: pretty_print_html(&injection.html),
+        target_files: find_target_html_files()
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn push_span(out: &mut String, class: &str, text: &str) {
+    out.push_str(&format!("<span class=\"{}\">{}</span>", class, escape_html(text)));
+}
+
+/// One tag, e.g. `<link rel="stylesheet">`, with its name, attribute names and
+/// quoted values highlighted.
+fn highlight_tag(out: &mut String, tag: &str) {
+    let name_start = if tag.starts_with("</") { 2 } else { 1 };
+    let name_end = tag[name_start..]
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .map_or(tag.len(), |i| name_start + i);
+    out.push_str(&escape_html(&tag[..name_start]));
+    push_span(out, "tag", &tag[name_start..name_end]);
+
+    let mut rest = &tag[name_end..];
+    while let Some(c) = rest.chars().next() {
+        let len = if c == '"' || c == '\'' {
+            let len = rest[1..].find(c).map_or(rest.len(), |i| i + 2);
+            push_span(out, "value", &rest[..len]);
+            len
+        } else if c.is_alphabetic() {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(rest.len());
+            push_span(out, "attr", &rest[..len]);
+            len
+        } else {
+            out.push_str(&escape_html(&rest[..c.len_utf8()]));
+            c.len_utf8()
         };
+        rest = &rest[len..];
+    }
+}
 
-        if is_patched(&content) {
+/// `html` escaped for display inside `<pre>`, with comments and tags highlighted.
+fn highlight_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() * 2);
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&escape_html(&rest[..start]));
+        rest = &rest[start..];
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |i| i + 3);
+            push_span(&mut out, "comment", &rest[..end]);
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            highlight_tag(&mut out, &rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    out.push_str(&escape_html(rest));
+    out
+}
+
+/// Write the injection `install()` would add, as a highlighted standalone page, to
+/// `debug_injection.html` in the uprooted directory. Nothing is patched.
+pub fn dump_injection() -> Result<PathBuf, UprootedError> {
+    let injection = render_injection();
+    let page = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Uprooted injection</title>
+<style>
+  body {{ margin: 0; padding: 24px; background: #0d1117; color: #c9d1d9; }}
+  p {{ color: #8b949e; font-family: sans-serif; }}
+  pre {{
+    padding: 16px;
+    background: #161b22;
+    border-radius: 6px;
+    white-space: pre-wrap;
+    word-break: break-all;
+    font: 13px/1.5 monospace;
+  }}
+  .comment {{ color: #8b949e; font-style: italic; }}
+  .tag {{ color: #7ee787; }}
+  .attr {{ color: #79c0ff; }}
+  .value {{ color: #a5d6ff; }}
+</style>
+</head>
+<body>
+<p>preload {version}, {bytes} bytes, generated {generated}</p>
+<pre>{html}</pre>
+</body>
+</html>
+"#,
+        version = artifact_meta::PRELOAD_JS_VERSION,
+        bytes = injection.html.len(),
+        generated = chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        html = highlight_html(&injection.html),
+    );
+
+    let dir = hook::get_uprooted_dir();
+    let path = dir.join(INJECTION_DEBUG_FILE);
+    fs::create_dir_all(&dir)
+        .and_then(|()| fs::write(&path, page))
+        .map_err(|e| UprootedError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(path)
+}
+
+/// Minified HTML is (nearly) one long line, so indentation would only add noise.
+fn is_minified_html(content: &str) -> bool {
+    content.matches('\n').count() < MINIFIED_MAX_NEWLINES
+}
+
+/// Add the block before `</head>`. When `</head>` starts its line the block goes on
+/// lines of its own above it; otherwise (minified HTML, or `</head>` after other
+/// markup) it is inlined right before it. Either way the original text is left intact,
+/// so `strip_injection()` gives back the exact bytes.
+fn inject_html(content: &str, html: &str) -> String {
+    let inline: String = html.lines().map(str::trim).collect();
+    if is_minified_html(content) {
+        return content.replace("</head>", &format!("{}</head>", inline));
+    }
+
+    let mut injected = String::with_capacity(content.len() + html.len() * 2);
+    for line in content.split_inclusive('\n') {
+        if !line.contains("</head>") {
+            injected.push_str(line);
+        } else if line.trim_start().starts_with("</head>") {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            for tag in html.lines().map(str::trim) {
+                let nesting = if tag == MARKER_START || tag == MARKER_END { "  " } else { "    " };
+                injected.push_str(indent);
+                injected.push_str(nesting);
+                injected.push_str(tag);
+                injected.push('\n');
+            }
+            injected.push_str(line);
+        } else {
+            injected.push_str(&line.replace("</head>", &format!("{}</head>", inline)));
+        }
+    }
+    injected
+}
+
+/// Byte range of the `content` attribute value of the page's CSP `<meta>` tag.
+fn csp_value_range(content: &str) -> Option<std::ops::Range<usize>> {
+    // ASCII lowercasing keeps byte offsets, so ranges in `lower` apply to `content`.
+    let lower = content.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(offset) = lower[from..].find("<meta") {
+        let start = from + offset;
+        let end = lower[start..].find('>').map_or(lower.len(), |len| start + len);
+        from = end;
+        let tag = &lower[start..end];
+        if !tag.contains("http-equiv") || !tag.contains("content-security-policy") {
             continue;
         }
+        let Some(attr) = tag.find("content=") else {
+            continue;
+        };
+        let quoted = start + attr + "content=".len();
+        let quote = content[quoted..end].chars().next();
+        let Some(quote) = quote.filter(|&c| c == '"' || c == '\'') else {
+            continue;
+        };
+        let len = content[quoted + 1..end].find(quote)?;
+        return Some(quoted + 1..quoted + 1 + len);
+    }
+    None
+}
+
+/// Whether a CSP directive is one of `CSP_DIRECTIVES` without a `file:` source.
+fn blocks_file_uris(directive: &str) -> bool {
+    let mut tokens = directive.split_ascii_whitespace();
+    tokens
+        .next()
+        .is_some_and(|name| CSP_DIRECTIVES.iter().any(|d| name.eq_ignore_ascii_case(d)))
+        && !tokens.any(|source| source.eq_ignore_ascii_case("file:"))
+}
+
+/// The page's Content-Security-Policy, if its `default-src`, `script-src` or
+/// `style-src` would block the injected `file://` script and stylesheets.
+pub fn detect_csp_restriction(content: &str) -> Option<String> {
+    let policy = &content[csp_value_range(content)?];
+    policy
+        .split(';')
+        .any(blocks_file_uris)
+        .then(|| policy.to_string())
+}
 
-        // Backup original
-        let backup_path_str = format!("{}{}", file.to_string_lossy(), BACKUP_SUFFIX);
-        let backup_path = Path::new(&backup_path_str);
-        if !backup_path.exists() {
-            if let Err(e) = fs::copy(file, backup_path) {
-                return PatchResult {
-                    success: false,
-                    message: format!("Failed to backup {}: {}", file.display(), e),
-                    files_patched: patched,
-                };
+/// `policy` with whichever of `CSP_SOURCES` are missing appended to each directive
+/// that blocks `file:`. Everything else is kept byte for byte.
+fn widen_csp(policy: &str) -> String {
+    policy
+        .split(';')
+        .map(|directive| {
+            if !blocks_file_uris(directive) {
+                return directive.to_string();
             }
+            let body = directive.trim_end();
+            let missing: Vec<&str> = CSP_SOURCES
+                .iter()
+                .copied()
+                .filter(|s| !directive.split_ascii_whitespace().any(|t| t.eq_ignore_ascii_case(s)))
+                .collect();
+            format!("{} {}{}", body, missing.join(" "), &directive[body.len()..])
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// `content` with `file: blob: data:` added to the CSP directives that would block
+/// the injection. Pages without a CSP `<meta>` tag are returned unchanged.
+fn inject_csp_override(content: &str) -> String {
+    let mut widened = content.to_string();
+    if let Some(range) = csp_value_range(content) {
+        widened.replace_range(range.clone(), &widen_csp(&content[range]));
+    }
+    widened
+}
+
+/// Put back the policy `inject_csp_override()` widened, using the original recorded
+/// in the injection block. The block itself is left for `strip_injection()`.
+fn restore_csp(content: &str) -> Cow<'_, str> {
+    let original = content.find(CSP_MARKER_PREFIX).and_then(|start| {
+        let rest = &content[start + CSP_MARKER_PREFIX.len()..];
+        rest.find(" -->").map(|end| &rest[..end])
+    });
+    match (original, csp_value_range(content)) {
+        (Some(original), Some(range)) if content[range.clone()] == widen_csp(original) => {
+            let mut restored = content.to_string();
+            restored.replace_range(range, original);
+            Cow::Owned(restored)
         }
+        _ => Cow::Borrowed(content),
+    }
+}
 
-        // Inject before </head>
-        let new_content = content.replace("</head>", &format!("    {}\n  </head>", injection));
-        if let Err(e) = fs::write(file, &new_content) {
-            return PatchResult {
-                success: false,
-                message: format!("Failed to write {}: {}", file.display(), e),
-                files_patched: patched,
-            };
+/// Back up `file` (once, unless backups are timestamped) and write `content` with the
+/// injection added before `</head>`. Returns a warning if the page's CSP blocks the
+/// injection and it was left alone.
+fn inject_into(
+    file: &Path,
+    content: &str,
+    encoding: HtmlEncoding,
+    injection: &Injection,
+) -> Result<Option<String>, UprootedError> {
+    backup_clean_file(file, false)?;
+
+    let mut csp_warning = None;
+    let new_content = match detect_csp_restriction(content) {
+        // The original goes in an HTML comment, so it must not end one or span lines.
+        Some(policy) if injection.fix_csp && !policy.contains("--") && !policy.contains('\n') => {
+            let marker = format!("{}\n{}{} -->", MARKER_START, CSP_MARKER_PREFIX, policy);
+            inject_html(
+                &inject_csp_override(content),
+                &injection.html.replacen(MARKER_START, &marker, 1),
+            )
         }
+        Some(policy) => {
+            csp_warning = Some(format!(
+                "{} has a Content-Security-Policy that blocks file: URIs ({}); \
+                 Uprooted may not load there.",
+                file.display(),
+                policy
+            ));
+            inject_html(content, &injection.html)
+        }
+        None => inject_html(content, &injection.html),
+    };
+    encoding::write_html(file, &new_content, encoding).map_err(|e| {
+        UprootedError::PatchFailed(format!("Failed to write {}: {}", file.display(), e))
+    })?;
+    Ok(csp_warning)
+}
+
+/// Patch every target file. With `continue_on_error`, a file that can't be read or
+/// written is recorded in `errors` and the rest are still patched; otherwise the
+/// first failure stops the install.
+pub fn install(continue_on_error: bool) -> PatchResult {
+    if assert_enabled(&load_settings()).is_err() {
+        return PatchResult::disabled();
+    }
 
-        patched.push(file.to_string_lossy().to_string());
+    let injection = match build_injection() {
+        Ok(i) => i,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+
+    let targets = find_target_html_files();
+    let span = crate::logging::timed(tracing::info_span!(
+        "install",
+        file_count = targets.len(),
+        patched_count = tracing::field::Empty
+    ));
+    if targets.is_empty() {
+        return PatchResult::failed(&UprootedError::NoTargetFiles, vec![]);
+    }
+
+    let mut patched = Vec::new();
+    let mut failures = Vec::new();
+    let mut warnings = Vec::new();
+    let mut csp_warnings = Vec::new();
+    for file in &targets {
+        let result = encoding::detect_and_read_html(file).and_then(|(content, encoding)| {
+            if is_patched(&content) {
+                return Ok(false);
+            }
+            if let Some(foreign) = detect_foreign_injection(&content) {
+                warnings.push(format!(
+                    "Skipped {}: already modified by {} ({}).",
+                    file.display(),
+                    foreign.framework_name,
+                    foreign.marker_found
+                ));
+                return Ok(false);
+            }
+            let csp_warning = inject_into(file, &content, encoding, &injection)?;
+            csp_warnings.extend(csp_warning);
+            Ok(true)
+        });
+
+        match result {
+            Ok(true) => patched.push(file.to_string_lossy().to_string()),
+            Ok(false) => {}
+            Err(e) if continue_on_error => failures.push(e),
+            Err(e) => return PatchResult::failed(&e, patched),
+        }
+    }
+
+    span.record("patched_count", patched.len());
+    let csp_warning = (!csp_warnings.is_empty()).then(|| csp_warnings.join("\n"));
+
+    if let Some(first) = failures.first() {
+        return PatchResult {
+            success: false,
+            message: format!(
+                "Uprooted partially installed. {} files patched, {} failed.",
+                patched.len(),
+                failures.len()
+            ),
+            files_patched: patched,
+            recovery_hint: suggest_recovery(first),
+            error_code: Some(first.code()),
+            size_warning: injection.size_warning,
+            errors: failures.iter().map(|e| e.to_string()).collect(),
+            warnings,
+            csp_warning,
+        };
     }
 
     PatchResult {
         success: true,
         message: format!("Uprooted installed. {} files patched.", patched.len()),
         files_patched: patched,
+        recovery_hint: None,
+        error_code: None,
+        size_warning: injection.size_warning,
+        errors: vec![],
+        warnings,
+        csp_warning,
+    }
+}
+
+/// A patched file that didn't read back with a complete injection block.
+#[derive(Serialize, Clone, Debug)]
+pub struct ValidationError {
+    pub file: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.file, self.reason)
+    }
+}
+
+/// What is wrong with the injection in `content`, if anything. A good one is a
+/// single start marker, then the version comment, then a single end marker.
+fn injection_problem(content: &str) -> Option<String> {
+    if !is_patched(content) {
+        return Some("the injection is missing".to_string());
+    }
+    let starts = content.matches(MARKER_START).count();
+    let ends = content.matches(MARKER_END).count();
+    if starts != 1 || ends != 1 {
+        return Some(format!("found {} start and {} end markers", starts, ends));
+    }
+    let start = content.find(MARKER_START)?;
+    let end = content.find(MARKER_END)?;
+    if end < start {
+        return Some("the end marker comes before the start marker".to_string());
+    }
+    if injection_version(&content[start..end]).is_none() {
+        return Some("the version comment is missing".to_string());
+    }
+    None
+}
+
+/// Read every patched file back from disk and check its injection block, to catch
+/// writes that were cut short or never reached the disk.
+pub fn validate_install(patched_files: &[PathBuf]) -> Vec<ValidationError> {
+    patched_files
+        .iter()
+        .filter_map(|file| {
+            let reason = match encoding::detect_and_read_html(file) {
+                Ok((content, _)) => injection_problem(&content)?,
+                Err(e) => format!("could not be read back: {}", e),
+            };
+            Some(ValidationError {
+                file: file.to_string_lossy().to_string(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Resolve a user-supplied path and require it to be inside the profile or uprooted directory.
+fn resolve_custom_target(path: &str) -> Result<PathBuf, UprootedError> {
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| UprootedError::ReadFailed(format!("{}: {}", path, e)))?;
+    let allowed = [get_profile_dir(), hook::get_uprooted_dir()];
+    if allowed
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| resolved.starts_with(dir))
+    {
+        Ok(resolved)
+    } else {
+        Err(UprootedError::PathTraversal(path.to_string()))
+    }
+}
+
+fn looks_like_html(content: &str) -> bool {
+    let head = content.trim_start().to_lowercase();
+    head.starts_with("<!doctype html") || head.contains("<html")
+}
+
+/// Patch one HTML file outside the auto-discovered targets and remember it in
+/// `custom_targets` so repairs keep it patched.
+pub fn patch_file(path: &str) -> PatchResult {
+    let mut settings = load_settings();
+    if assert_enabled(&settings).is_err() {
+        return PatchResult::disabled();
+    }
+    if let Err(e) = storage::assert_writable() {
+        return PatchResult::failed(&e, vec![]);
+    }
+
+    let file = match resolve_custom_target(path) {
+        Ok(f) => f,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    let (content, encoding) = match encoding::detect_and_read_html(&file) {
+        Ok(read) => read,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    if !looks_like_html(&content) {
+        let error = UprootedError::PatchFailed(format!("{} is not an HTML file", file.display()));
+        return PatchResult::failed(&error, vec![]);
+    }
+
+    let mut csp_warning = None;
+    let message = if is_patched(&content) {
+        format!("{} is already patched.", file.display())
+    } else {
+        if let Some(foreign) = detect_foreign_injection(&content) {
+            let error = UprootedError::PatchFailed(format!(
+                "{} is already modified by {} ({})",
+                file.display(),
+                foreign.framework_name,
+                foreign.marker_found
+            ));
+            return PatchResult::failed(&error, vec![]);
+        }
+        let injection = match build_injection() {
+            Ok(i) => i,
+            Err(e) => return PatchResult::failed(&e, vec![]),
+        };
+        match inject_into(&file, &content, encoding, &injection) {
+            Ok(warning) => csp_warning = warning,
+            Err(e) => return PatchResult::failed(&e, vec![]),
+        }
+        format!("Patched {}.", file.display())
+    };
+
+    // Only remembered once the file is patched, so a failed attempt isn't repaired later.
+    let file_str = file.to_string_lossy().to_string();
+    if !settings.custom_targets.contains(&file_str) {
+        settings.custom_targets.push(file_str.clone());
+        if let Err(e) = save_settings(&settings) {
+            return PatchResult::failed(&UprootedError::Io(e), vec![file_str]);
+        }
+    }
+
+    PatchResult {
+        success: true,
+        message,
+        files_patched: vec![file_str],
+        recovery_hint: None,
+        error_code: None,
+        size_warning: None,
+        errors: vec![],
+        warnings: vec![],
+        csp_warning,
+    }
+}
+
+/// Remove the injection from a patched file and delete its backups. Returns whether
+/// the file was restored.
+fn restore_file(file: &Path, content: &str, encoding: HtmlEncoding) -> Result<bool, UprootedError> {
+    // Prefer stripping in-place (preserves current Root HTML)
+    let cleaned = strip_injection(content);
+    if cleaned != content {
+        // Backups are only deleted once the file no longer needs them.
+        encoding::write_html(file, &cleaned, encoding).map_err(|e| {
+            UprootedError::PatchFailed(format!("Failed to write {}: {}", file.display(), e))
+        })?;
+    } else if let Some(backup_path) = latest_backup_for(file) {
+        // Fallback: restore from backup if stripping didn't change anything
+        fs::copy(&backup_path, file).map_err(|e| {
+            UprootedError::PatchFailed(format!("Failed to restore {}: {}", file.display(), e))
+        })?;
+    } else {
+        return Ok(false);
+    }
+
+    for backup_path in all_backups_for(file) {
+        let _ = fs::remove_file(backup_path);
+    }
+    Ok(true)
+}
+
+/// Root's HTML files and their backups as they were before a staged install.
+pub struct HtmlSnapshot {
+    targets: Vec<PathBuf>,
+    files: crate::storage::FileSnapshot,
+}
+
+impl HtmlSnapshot {
+    pub fn take() -> Self {
+        let targets = find_target_html_files();
+        let paths: Vec<PathBuf> = targets
+            .iter()
+            .flat_map(|file| std::iter::once(file.clone()).chain(all_backups_for(file)))
+            .collect();
+        HtmlSnapshot {
+            files: crate::storage::snapshot_files(paths),
+            targets,
+        }
+    }
+
+    /// Put the files back and delete backups written since.
+    pub fn restore(&self) -> Result<(), String> {
+        crate::storage::restore_snapshot(&self.files)?;
+        for backup in self.targets.iter().flat_map(|file| all_backups_for(file)) {
+            if !self.files.iter().any(|(p, _)| *p == backup) {
+                let _ = fs::remove_file(backup);
+            }
+        }
+        Ok(())
     }
 }
 
 pub fn uninstall() -> PatchResult {
     let targets = find_target_html_files();
+    let span = crate::logging::timed(tracing::info_span!(
+        "uninstall",
+        file_count = targets.len(),
+        restored_count = tracing::field::Empty
+    ));
     let mut restored = Vec::new();
 
     for file in &targets {
-        let content = match fs::read_to_string(file) {
-            Ok(c) => c,
-            Err(_) => continue,
+        let Ok((content, encoding)) = encoding::detect_and_read_html(file) else {
+            continue;
         };
 
         if !is_patched(&content) {
             continue;
         }
 
-        // Prefer stripping in-place (preserves current Root HTML)
-        let cleaned = strip_injection(&content);
-        if cleaned != content {
-            let _ = fs::write(file, &cleaned);
-            restored.push(file.to_string_lossy().to_string());
-
-            // Clean up backup file if it exists
-            let backup_path_str = format!("{}{}", file.to_string_lossy(), BACKUP_SUFFIX);
-            let _ = fs::remove_file(Path::new(&backup_path_str));
-            continue;
-        }
-
-        // Fallback: restore from backup if stripping didn't change anything
-        let backup_path_str = format!("{}{}", file.to_string_lossy(), BACKUP_SUFFIX);
-        let backup_path = Path::new(&backup_path_str);
-        if backup_path.exists() {
-            if let Err(e) = fs::copy(backup_path, file) {
-                return PatchResult {
-                    success: false,
-                    message: format!("Failed to restore {}: {}", file.display(), e),
-                    files_patched: restored,
-                };
-            }
-            let _ = fs::remove_file(backup_path);
-            restored.push(file.to_string_lossy().to_string());
+        match restore_file(file, &content, encoding) {
+            Ok(true) => restored.push(file.to_string_lossy().to_string()),
+            Ok(false) => {}
+            Err(e) => return PatchResult::failed(&e, restored),
         }
     }
+    span.record("restored_count", restored.len());
 
     PatchResult {
         success: true,
@@ -162,16 +1018,83 @@ pub fn uninstall() -> PatchResult {
             restored.len()
         ),
         files_patched: restored,
+        recovery_hint: None,
+        error_code: None,
+        size_warning: None,
+        errors: vec![],
+        warnings: vec![],
+        csp_warning: None,
+    }
+}
+
+/// Revert a single file and drop it from `custom_targets`.
+pub fn unpatch_file(path: &str) -> PatchResult {
+    let file = match resolve_custom_target(path) {
+        Ok(f) => f,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    let (content, encoding) = match encoding::detect_and_read_html(&file) {
+        Ok(read) => read,
+        Err(e) => return PatchResult::failed(&e, vec![]),
+    };
+    if !is_patched(&content) {
+        let error = UprootedError::PatchFailed(format!("{} is not patched by Uprooted", file.display()));
+        return PatchResult::failed(&error, vec![]);
+    }
+
+    if let Err(e) = restore_file(&file, &content, encoding) {
+        return PatchResult::failed(&e, vec![]);
+    }
+
+    let file_str = file.to_string_lossy().to_string();
+    let mut settings = load_settings();
+    if settings.custom_targets.contains(&file_str) {
+        settings.custom_targets.retain(|t| t != &file_str);
+        if let Err(e) = save_settings(&settings) {
+            return PatchResult::failed(&UprootedError::Io(e), vec![file_str]);
+        }
+    }
+
+    PatchResult {
+        success: true,
+        message: format!("Restored {}.", file.display()),
+        files_patched: vec![file_str],
+        recovery_hint: None,
+        error_code: None,
+        size_warning: None,
+        errors: vec![],
+        warnings: vec![],
+        csp_warning: None,
     }
 }
 
 /// Strip injected content between start/end markers, legacy markers, and bare uprooted tags
 /// (from bash installer which historically didn't use markers).
-fn strip_injection(content: &str) -> String {
+pub fn strip_injection(content: &str) -> String {
+    let content = &*restore_csp(content);
     let mut result = Vec::new();
     let mut inside_block = false;
+    // Inside someone else's multi-line `<!-- ... -->`; kept verbatim.
+    let mut inside_comment = false;
+    // Inside `<script>`/`<style>`, where `<!--` is JS or CSS text, not a comment.
+    let mut inside_raw_text = false;
 
     for line in content.lines() {
+        // An inlined block shares its line with the page, which may close a comment.
+        let inline = strip_inline_blocks(line);
+        if inside_comment {
+            let line = inline.map_or(Cow::Borrowed(line), Cow::Owned);
+            inside_comment = !line.contains("-->");
+            result.push(line);
+            continue;
+        }
+        if let Some(kept) = inline {
+            if !kept.trim().is_empty() {
+                inside_comment = opens_comment(outside_raw_text(&kept, &mut inside_raw_text));
+                result.push(Cow::Owned(kept));
+            }
+            continue;
+        }
         if line.contains(MARKER_START) {
             inside_block = true;
             continue;
@@ -187,44 +1110,273 @@ fn strip_injection(content: &str) -> String {
         if line.contains(LEGACY_MARKER) {
             continue;
         }
-        // Bare uprooted tags (bash installer without markers)
-        if line.contains("uprooted-preload")
-            && (line.contains("<script") || line.contains("</script"))
-        {
+        // Bare uprooted tags (bash installer without markers), unless commented out
+        let trimmed = line.trim();
+        let commented = trimmed.starts_with("<!--") && trimmed.ends_with("-->");
+        if !commented && is_bare_injection_tag(line) {
             continue;
         }
-        if line.contains("uprooted.css") && line.contains("<link") {
-            continue;
+        inside_comment = opens_comment(outside_raw_text(line, &mut inside_raw_text));
+        result.push(Cow::Borrowed(line));
+    }
+
+    let mut stripped = result.join("\n");
+    if content.ends_with('\n') {
+        stripped.push('\n');
+    }
+    stripped
+}
+
+/// `line` with every marker block that starts and ends on it removed, or `None`
+/// if it has none.
+fn strip_inline_blocks(line: &str) -> Option<String> {
+    let mut kept = line.to_string();
+    let mut found = false;
+    while let Some(start) = kept.find(MARKER_START) {
+        let Some(len) = kept[start..].find(MARKER_END) else {
+            break;
+        };
+        kept.replace_range(start..start + len + MARKER_END.len(), "");
+        found = true;
+    }
+    found.then_some(kept)
+}
+
+fn is_bare_injection_tag(line: &str) -> bool {
+    (line.contains("uprooted-preload") && (line.contains("<script") || line.contains("</script")))
+        || (line.contains("uprooted.css") && line.contains("<link"))
+        || (line.contains("uprooted/plugins/") && line.contains("<link"))
+        || (line.contains("__UPROOTED_SETTINGS__") && line.contains("<script"))
+        || (line.contains("__UPROOTED_META__") && line.contains("<script"))
+}
+
+/// The part of `line` that is HTML markup rather than `<script>`/`<style>` content,
+/// updating `inside` for the next line.
+fn outside_raw_text<'a>(line: &'a str, inside: &mut bool) -> &'a str {
+    let lower = line.to_ascii_lowercase();
+    let last_open = ["<script", "<style"].iter().filter_map(|t| lower.rfind(t)).max();
+    let last_close = ["</script", "</style"].iter().filter_map(|t| lower.rfind(t)).max();
+    match (last_open, last_close) {
+        (Some(open), Some(close)) if open < close => {
+            *inside = false;
+            &line[close..]
         }
-        if line.contains("__UPROOTED_SETTINGS__") && line.contains("<script") {
-            continue;
+        (Some(_), _) => {
+            *inside = true;
+            ""
+        }
+        (None, Some(close)) => {
+            *inside = false;
+            &line[close..]
         }
-        result.push(line);
+        (None, None) if *inside => "",
+        (None, None) => line,
     }
+}
 
-    result.join("\n")
+/// Whether `line` starts a comment that it doesn't close.
+fn opens_comment(line: &str) -> bool {
+    line.rfind("<!--")
+        .is_some_and(|start| !line[start + 4..].contains("-->"))
 }
 
 pub fn repair() -> PatchResult {
+    if assert_enabled(&load_settings()).is_err() {
+        return PatchResult::disabled();
+    }
+
     let targets = find_target_html_files();
+    let _span = crate::logging::timed(tracing::info_span!("repair", file_count = targets.len()));
 
     // Strip existing injection in-place (preserves Root's current HTML)
     for file in &targets {
-        let content = match fs::read_to_string(file) {
-            Ok(c) => c,
-            Err(_) => continue,
+        let Ok((content, encoding)) = encoding::detect_and_read_html(file) else {
+            continue;
         };
 
         if is_patched(&content) {
             let cleaned = strip_injection(&content);
-            let _ = fs::write(file, &cleaned);
-
-            // Update backup to current clean state
-            let backup_path_str = format!("{}{}", file.to_string_lossy(), BACKUP_SUFFIX);
-            let _ = fs::write(Path::new(&backup_path_str), &cleaned);
+            if encoding::write_html(file, &cleaned, encoding).is_ok() {
+                // Update backup to current clean state
+                let _ = backup_clean_file(file, true);
+            }
         }
     }
 
     // Re-install fresh patches
-    install()
+    install(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::UprootedSettings;
+
+    #[test]
+    fn settings_json_cannot_close_script_tag() {
+        let settings = UprootedSettings {
+            custom_css: "</script><script>alert(1)</script><!-- x".to_string(),
+            ..UprootedSettings::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        let escaped = json_escape_for_html(&json);
+        let tag = format!("<script>window.__UPROOTED_SETTINGS__={};</script>", escaped);
+
+        assert_eq!(tag.matches("</script").count(), 1);
+        assert!(tag.ends_with("</script>"));
+        assert!(!escaped.contains("<!--"));
+
+        let parsed: UprootedSettings = serde_json::from_str(&escaped).unwrap();
+        assert_eq!(parsed.custom_css, settings.custom_css);
+    }
+
+    const ORIGINAL: &str = "<!DOCTYPE html>\n<html>\n  <head>\n    <title>Root</title>\n  </head>\n  <body></body>\n</html>\n";
+
+    /// Shaped like `render_injection()`'s output, without reading the real settings file.
+    fn fixed_injection() -> String {
+        format!(
+            "{}\n    {}0.2.3 -->\n    <script>window.__UPROOTED_SETTINGS__={{}};</script>\n    \
+             <script src=\"file:///x/uprooted-preload.js\"></script>\n    \
+             <link rel=\"stylesheet\" href=\"file:///x/uprooted.css\">\n    {}",
+            MARKER_START, VERSION_MARKER_PREFIX, MARKER_END
+        )
+    }
+
+    fn with_head_lines(lines: &[&str]) -> String {
+        let extra: String = lines.iter().map(|l| format!("    {}\n", l)).collect();
+        ORIGINAL.replace("  </head>", &format!("{}  </head>", extra))
+    }
+
+    #[test]
+    fn strips_marker_block() {
+        let patched = with_head_lines(&[
+            MARKER_START,
+            "<!-- uprooted:version 0.2.3 -->",
+            "<script>window.__UPROOTED_SETTINGS__={};</script>",
+            "<script src=\"file:///x/uprooted-preload.js\"></script>",
+            "<link rel=\"stylesheet\" href=\"file:///x/uprooted.css\">",
+            MARKER_END,
+        ]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn strips_legacy_marker() {
+        let patched = with_head_lines(&[LEGACY_MARKER]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn strips_bare_preload_script() {
+        let patched = with_head_lines(&["<script src=\"file:///home/u/.local/share/uprooted/uprooted-preload.js\"></script>"]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn strips_bare_stylesheet_link() {
+        let patched = with_head_lines(&["<link rel=\"stylesheet\" href=\"file:///home/u/.local/share/uprooted/uprooted.css\">"]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn strips_inline_settings_script() {
+        let patched = with_head_lines(&["<script>window.__UPROOTED_SETTINGS__={\"enabled\":true};</script>"]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn strips_inline_meta_script() {
+        let patched = with_head_lines(&["<script>window.__UPROOTED_META__={\"version\":\"0.2.3\"};</script>"]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn strips_mixed_formats() {
+        let patched = with_head_lines(&[
+            LEGACY_MARKER,
+            "<script>window.__UPROOTED_SETTINGS__={};</script>",
+            "<script src=\"file:///x/uprooted-preload.js\"></script>",
+            MARKER_START,
+            "<link rel=\"stylesheet\" href=\"file:///x/uprooted.css\">",
+            MARKER_END,
+            "<link rel=\"stylesheet\" href=\"file:///x/uprooted.css\">",
+        ]);
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn leaves_clean_file_unchanged() {
+        assert_eq!(strip_injection(ORIGINAL), ORIGINAL);
+    }
+
+    #[test]
+    fn keeps_tags_inside_comments() {
+        let commented = with_head_lines(&[
+            "<!--",
+            "<script src=\"file:///x/uprooted-preload.js\"></script>",
+            "<link rel=\"stylesheet\" href=\"file:///x/uprooted.css\">",
+            "-->",
+            "<!-- <script src=\"file:///x/uprooted-preload.js\"></script> -->",
+        ]);
+        assert_eq!(strip_injection(&commented), commented);
+    }
+
+    #[test]
+    fn comment_opener_in_script_does_not_block_strip() {
+        let page = with_head_lines(&["<script>", "var open = \"<!--\";", "</script>"]);
+        let patched = inject_html(&page, "<script src=\"file:///x/uprooted-preload.js\"></script>");
+        assert!(is_patched(&patched));
+        assert_eq!(strip_injection(&patched), page);
+    }
+
+    #[test]
+    fn strip_undoes_inject() {
+        let patched = inject_html(ORIGINAL, &fixed_injection());
+        assert!(is_patched(&patched));
+        assert_eq!(strip_injection(&patched), ORIGINAL);
+    }
+
+    #[test]
+    fn minified_injection_stays_on_one_line() {
+        const MINIFIED: &str =
+            "<!DOCTYPE html><html><head><title>Root</title></head><body></body></html>";
+        let patched = inject_html(MINIFIED, &fixed_injection());
+
+        assert_eq!(patched.lines().count(), 1);
+        assert!(patched.contains(&format!("{}</head>", MARKER_END)));
+        assert!(!patched.contains("    "));
+        assert_eq!(strip_injection(&patched), MINIFIED);
+    }
+
+    const CSP_META: &str =
+        "<meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'self'; img-src *\">";
+
+    #[test]
+    fn detects_csp_that_blocks_file_uris() {
+        let page = with_head_lines(&[CSP_META]);
+        assert_eq!(
+            detect_csp_restriction(&page).as_deref(),
+            Some("default-src 'self'; img-src *")
+        );
+        assert_eq!(detect_csp_restriction(ORIGINAL), None);
+
+        let allowed = page.replace("'self';", "'self' file:;");
+        assert_eq!(detect_csp_restriction(&allowed), None);
+    }
+
+    #[test]
+    fn strip_restores_widened_csp() {
+        let page = with_head_lines(&[CSP_META]);
+        let policy = detect_csp_restriction(&page).unwrap();
+        let html = fixed_injection().replacen(
+            MARKER_START,
+            &format!("{}\n{}{} -->", MARKER_START, CSP_MARKER_PREFIX, policy),
+            1,
+        );
+        let patched = inject_html(&inject_csp_override(&page), &html);
+
+        assert!(patched.contains("default-src 'self' file: blob: data:; img-src *"));
+        assert_eq!(detect_csp_restriction(&patched), None);
+        assert_eq!(strip_injection(&patched), page);
+    }
 }
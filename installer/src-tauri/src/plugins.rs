@@ -0,0 +1,292 @@
+use crate::error::UprootedError;
+use crate::hook::get_uprooted_dir;
+use crate::settings::{PluginSettings, UprootedSettings};
+use crate::themes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// `manifest.json` shipped inside each plugin directory.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub entry_js: Option<String>,
+    /// Stylesheet deployed as `style.css` in the plugin directory and linked into Root's HTML.
+    #[serde(default)]
+    pub entry_css: Option<String>,
+    /// Config values the plugin starts with; used by the reset commands.
+    #[serde(default)]
+    pub default_config: serde_json::Value,
+    /// JSON Schema for `config`, used to render the plugin's settings panel. Properties
+    /// may set `x-uprooted-widget` to `color-picker`, `file-path` or `toggle`.
+    #[serde(default)]
+    pub config_schema: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PluginInfo {
+    pub manifest: PluginManifest,
+    pub dir: String,
+    /// Absolute path of the entry script, present only if it resolved inside the plugin directory.
+    pub entry_js_path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Returns `<uprooted dir>/plugins`.
+pub fn get_plugins_dir() -> PathBuf {
+    get_uprooted_dir().join("plugins")
+}
+
+/// Resolve `relative` against `base` and make sure the result stays inside `base`.
+/// Both paths are canonicalized, so `..` segments and symlinks pointing outside are rejected.
+pub fn sanitize_plugin_path(base: &Path, relative: &str) -> Result<PathBuf, UprootedError> {
+    let rel = Path::new(relative);
+    if rel.is_absolute() || rel.components().any(|c| matches!(c, Component::Prefix(_))) {
+        return Err(UprootedError::PathTraversal(relative.to_string()));
+    }
+
+    let base = base
+        .canonicalize()
+        .map_err(|e| UprootedError::Io(format!("Failed to resolve {}: {}", base.display(), e)))?;
+    let joined = base.join(rel);
+    let resolved = joined
+        .canonicalize()
+        .map_err(|e| UprootedError::Io(format!("Failed to resolve {}: {}", joined.display(), e)))?;
+
+    if !resolved.starts_with(&base) {
+        return Err(UprootedError::PathTraversal(relative.to_string()));
+    }
+    Ok(resolved)
+}
+
+fn load_plugin(base: &Path, dir_name: &str) -> Option<PluginInfo> {
+    let manifest_path = sanitize_plugin_path(base, &format!("{}/{}", dir_name, MANIFEST_FILE)).ok()?;
+    let content = fs::read_to_string(&manifest_path).ok()?;
+    let manifest: PluginManifest = serde_json::from_str(&content).ok()?;
+
+    let mut info = PluginInfo {
+        manifest: manifest.clone(),
+        dir: base.join(dir_name).to_string_lossy().to_string(),
+        entry_js_path: None,
+        error: None,
+    };
+
+    if let Some(entry) = &manifest.entry_js {
+        match sanitize_plugin_path(&base.join(dir_name), entry) {
+            Ok(path) => info.entry_js_path = Some(path.to_string_lossy().to_string()),
+            Err(e) => info.error = Some(e.to_string()),
+        }
+    }
+
+    Some(info)
+}
+
+/// Scan the plugins directory for subdirectories containing a valid `manifest.json`.
+pub fn list_available_plugins() -> Vec<PluginInfo> {
+    let base = get_plugins_dir();
+    let entries = match fs::read_dir(&base) {
+        Ok(e) => e,
+        Err(_) => return vec![],
+    };
+
+    let mut plugins: Vec<PluginInfo> = entries
+        .flatten()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| load_plugin(&base, &e.file_name().to_string_lossy()))
+        .collect();
+    plugins.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    plugins
+}
+
+/// Name of each plugin's deployed stylesheet, inside its directory.
+const PLUGIN_CSS_FILE: &str = "style.css";
+
+/// Plugins without a settings entry are enabled.
+pub(crate) fn is_enabled(settings: &UprootedSettings, name: &str) -> bool {
+    settings.plugins.get(name).map(|p| p.enabled).unwrap_or(true)
+}
+
+/// Enabled plugins that ship a stylesheet, ordered by `priority` and then name.
+fn css_plugins(settings: &UprootedSettings) -> Vec<PluginInfo> {
+    let mut plugins: Vec<PluginInfo> = list_available_plugins()
+        .into_iter()
+        .filter(|p| p.manifest.entry_css.is_some() && is_enabled(settings, &p.manifest.name))
+        .collect();
+    plugins.sort_by_key(|p| {
+        let priority = settings.plugins.get(&p.manifest.name).map(|s| s.priority).unwrap_or(0);
+        (priority, p.manifest.name.clone())
+    });
+    plugins
+}
+
+/// Copy each enabled plugin's `entry_css` to `style.css` in its directory, where
+/// `plugin_css_files()` picks it up. A plugin whose stylesheet can't be resolved or
+/// copied is skipped with a warning rather than failing the deploy.
+pub fn deploy_plugin_css(settings: &UprootedSettings) {
+    for plugin in css_plugins(settings) {
+        let dir = PathBuf::from(&plugin.dir);
+        let entry = plugin.manifest.entry_css.as_deref().unwrap_or_default();
+        let target = dir.join(PLUGIN_CSS_FILE);
+        let copied = sanitize_plugin_path(&dir, entry).and_then(|source| {
+            // Copying a file onto itself would truncate it.
+            if target.canonicalize().is_ok_and(|t| t == source) {
+                return Ok(());
+            }
+            fs::copy(&source, &target).map(|_| ()).map_err(|e| {
+                UprootedError::Io(format!("Failed to copy {}: {}", source.display(), e))
+            })
+        });
+        if let Err(e) = copied {
+            tracing::warn!("Skipping CSS for plugin {}: {}", plugin.manifest.name, e);
+        }
+    }
+}
+
+/// Deployed plugin stylesheets to link after `uprooted.css`, in load order.
+pub fn plugin_css_files(settings: &UprootedSettings) -> Vec<PathBuf> {
+    named_plugin_css_files(settings)
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// `plugin_css_files()` paired with the name of the plugin each stylesheet belongs to.
+pub fn named_plugin_css_files(settings: &UprootedSettings) -> Vec<(String, PathBuf)> {
+    css_plugins(settings)
+        .into_iter()
+        .map(|p| {
+            let path = Path::new(&p.dir).join(PLUGIN_CSS_FILE);
+            (p.manifest.name, path)
+        })
+        .filter(|(_, path)| path.exists())
+        .collect()
+}
+
+/// Replace a plugin's config with the defaults from its manifest, keeping its enabled state.
+pub fn reset_config(settings: &mut UprootedSettings, manifest: &PluginManifest) {
+    let defaults: HashMap<String, serde_json::Value> = manifest
+        .default_config
+        .as_object()
+        .map(|o| o.clone().into_iter().collect())
+        .unwrap_or_default();
+
+    settings
+        .plugins
+        .entry(manifest.name.clone())
+        .or_insert_with(|| PluginSettings {
+            enabled: true,
+            config: HashMap::new(),
+            priority: 0,
+        })
+        .config = defaults;
+}
+
+pub fn get_config_schema(plugin_name: &str) -> Option<Value> {
+    list_available_plugins()
+        .into_iter()
+        .find(|p| p.manifest.name == plugin_name)
+        .and_then(|p| p.manifest.config_schema)
+}
+
+/// Check `config` against the parts of JSON Schema plugin settings use: `properties`
+/// with `type`, `enum`, `minimum` and `maximum`, plus `required` and
+/// `additionalProperties: false`. A `color-picker` property must hold a hex color.
+pub fn validate_config(schema: &Value, config: &HashMap<String, Value>) -> Result<(), String> {
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    for key in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(key) = key.as_str() {
+            if !config.contains_key(key) {
+                return Err(format!("'{}' is required", key));
+            }
+        }
+    }
+
+    for (key, value) in config {
+        match properties.and_then(|p| p.get(key)) {
+            Some(property) => validate_value(key, property, value)?,
+            None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                return Err(format!("'{}' is not a known setting", key));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn validate_value(key: &str, schema: &Value, value: &Value) -> Result<(), String> {
+    if let Some(ty) = schema.get("type").and_then(Value::as_str) {
+        let matches = match ty {
+            "string" => value.is_string(),
+            "boolean" => value.is_boolean(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!("'{}' must be of type {}", key, ty));
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return Err(format!("'{}' must be one of the allowed values", key));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if schema.get("minimum").and_then(Value::as_f64).is_some_and(|min| n < min) {
+            return Err(format!("'{}' is below the minimum", key));
+        }
+        if schema.get("maximum").and_then(Value::as_f64).is_some_and(|max| n > max) {
+            return Err(format!("'{}' is above the maximum", key));
+        }
+    }
+
+    if schema.get("x-uprooted-widget").and_then(Value::as_str) == Some("color-picker") {
+        if let Some(color) = value.as_str() {
+            themes::parse_hex(color).map_err(|e| format!("'{}': {}", key, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate `name`'s config against its manifest's `config_schema`, if it has one.
+pub fn validate_plugin_config(settings: &UprootedSettings, name: &str) -> Result<(), String> {
+    let Some(plugin_settings) = settings.plugins.get(name) else {
+        return Ok(());
+    };
+    let Some(schema) = get_config_schema(name) else {
+        return Ok(());
+    };
+    validate_config(&schema, &plugin_settings.config)
+        .map_err(|e| format!("Invalid config for plugin '{}': {}", name, e))
+}
+
+/// Validate the plugin configs in `new` that differ from `old`, so one plugin's stale
+/// config doesn't block saving another's.
+pub fn validate_changed_plugin_configs(
+    old: &UprootedSettings,
+    new: &UprootedSettings,
+) -> Result<(), String> {
+    for (name, plugin_settings) in &new.plugins {
+        if old.plugins.get(name).map(|p| &p.config) != Some(&plugin_settings.config) {
+            validate_plugin_config(new, name)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use crate::compat;
+use crate::detection;
+use crate::storage;
+use crate::system;
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CheckSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PrerequisiteCheck {
+    pub name: String,
+    pub passed: bool,
+    pub severity: CheckSeverity,
+    pub message: String,
+}
+
+impl PrerequisiteCheck {
+    fn new(name: &str, passed: bool, severity: CheckSeverity, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed,
+            severity,
+            message: message.into(),
+        }
+    }
+}
+
+/// Run all pre-install checks. A failed `Error` check should block installation.
+pub fn check_prerequisites() -> Vec<PrerequisiteCheck> {
+    let mut checks = Vec::new();
+
+    let in_wsl = detection::is_running_in_wsl();
+    checks.push(PrerequisiteCheck::new(
+        "Not running under WSL",
+        !in_wsl,
+        CheckSeverity::Error,
+        if in_wsl { detection::WSL_WARNING } else { "" },
+    ));
+
+    #[cfg(target_os = "windows")]
+    {
+        let registry = crate::hook::validate_registry_writable();
+        checks.push(PrerequisiteCheck::new(
+            "Registry is writable",
+            registry.is_ok(),
+            CheckSeverity::Error,
+            registry.err().map(|e| e.to_string()).unwrap_or_default(),
+        ));
+    }
+
+    let root_exe = detection::get_root_exe_path();
+    let root_found = root_exe.exists();
+    checks.push(PrerequisiteCheck::new(
+        "Root is installed",
+        root_found,
+        CheckSeverity::Warning,
+        if root_found {
+            String::new()
+        } else {
+            format!("Root was not found at {}", root_exe.display())
+        },
+    ));
+
+    let root_version = detection::get_root_version();
+    let compat_warning = compat::check_root_version(root_version.as_deref());
+    checks.push(PrerequisiteCheck::new(
+        "Root version is supported",
+        compat_warning.is_none(),
+        CheckSeverity::Error,
+        compat_warning.unwrap_or_default(),
+    ));
+
+    let dotnet_warning = system::check_dotnet_version(system::detect_dotnet_version().as_deref());
+    checks.push(PrerequisiteCheck::new(
+        ".NET runtime is supported",
+        dotnet_warning.is_none(),
+        CheckSeverity::Warning,
+        dotnet_warning.unwrap_or_default(),
+    ));
+
+    let profile = detection::get_profile_dir();
+    let profile_found = profile.exists();
+    checks.push(PrerequisiteCheck::new(
+        "Root profile exists",
+        profile_found,
+        CheckSeverity::Warning,
+        if profile_found {
+            ""
+        } else {
+            "Run Root at least once to create its profile directory."
+        },
+    ));
+
+    let writable = storage::validate_profile_writable();
+    checks.push(PrerequisiteCheck::new(
+        "Profile directory is writable",
+        writable.is_ok(),
+        CheckSeverity::Error,
+        writable.err().map(|e| e.to_string()).unwrap_or_default(),
+    ));
+
+    let enforcement = detection::detect_html_integrity_enforcement();
+    checks.push(PrerequisiteCheck::new(
+        "Root does not verify its HTML files",
+        enforcement.is_none(),
+        CheckSeverity::Warning,
+        match enforcement {
+            None => String::new(),
+            Some(info) => format!(
+                "Root appears to verify its HTML files against known hashes ({}). \
+                 Patched files may fail the check and Root may refuse to load them; \
+                 if Root stops loading after install, uninstall Uprooted.",
+                info.manifest_path
+            ),
+        },
+    ));
+
+    checks
+}
+
+/// The first failed `Error`-severity check, if any.
+pub fn first_blocking(checks: &[PrerequisiteCheck]) -> Option<&PrerequisiteCheck> {
+    checks
+        .iter()
+        .find(|c| !c.passed && c.severity == CheckSeverity::Error)
+}
@@ -0,0 +1,75 @@
+use crate::detection;
+use crate::hook::get_uprooted_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const RECEIPT_FILE: &str = "install-receipt.json";
+const SCHEMA_VERSION: u32 = 1;
+
+/// `install-receipt.json`, written next to the deployed files after a successful install
+/// so support can see when and with which versions Uprooted was installed.
+#[derive(Serialize, Deserialize)]
+pub struct InstallReceipt {
+    pub installed_at: String,
+    pub uprooted_version: String,
+    pub root_version: Option<String>,
+    pub files_patched: Vec<String>,
+    pub schema_version: u32,
+    #[serde(default)]
+    pub last_repaired_at: Option<String>,
+}
+
+fn receipt_path() -> PathBuf {
+    get_uprooted_dir().join(RECEIPT_FILE)
+}
+
+fn write(receipt: &InstallReceipt) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(receipt)
+        .map_err(|e| format!("Failed to serialize receipt: {}", e))?;
+    fs::write(receipt_path(), json).map_err(|e| format!("Failed to write {}: {}", RECEIPT_FILE, e))
+}
+
+pub fn read_receipt() -> Option<InstallReceipt> {
+    let content = fs::read_to_string(receipt_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn write_install_receipt(files_patched: &[String]) -> Result<(), String> {
+    write(&InstallReceipt {
+        installed_at: chrono::Utc::now().to_rfc3339(),
+        uprooted_version: env!("CARGO_PKG_VERSION").to_string(),
+        root_version: detection::get_root_version(),
+        files_patched: files_patched.to_vec(),
+        schema_version: SCHEMA_VERSION,
+        last_repaired_at: None,
+    })
+}
+
+/// Record a repair. The original install time is kept; installs without a receipt get a new one.
+pub fn record_repair(files_patched: &[String]) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let receipt = match read_receipt() {
+        Some(r) => InstallReceipt {
+            uprooted_version: env!("CARGO_PKG_VERSION").to_string(),
+            root_version: detection::get_root_version(),
+            files_patched: files_patched.to_vec(),
+            schema_version: SCHEMA_VERSION,
+            last_repaired_at: Some(now),
+            ..r
+        },
+        None => InstallReceipt {
+            installed_at: now.clone(),
+            uprooted_version: env!("CARGO_PKG_VERSION").to_string(),
+            root_version: detection::get_root_version(),
+            files_patched: files_patched.to_vec(),
+            schema_version: SCHEMA_VERSION,
+            last_repaired_at: Some(now),
+        },
+    };
+    write(&receipt)
+}
+
+pub fn remove_receipt() {
+    let _ = fs::remove_file(receipt_path());
+}
@@ -0,0 +1,218 @@
+use serde::Serialize;
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+const SERVICE_NAME: &str = "uprooted-monitor.service";
+
+#[derive(Serialize)]
+pub struct ServiceStatus {
+    pub installed: bool,
+    pub active: bool,
+    /// Raw `systemctl --user is-active` output, e.g. "active", "inactive", "failed".
+    pub state: String,
+}
+
+/// The systemd user unit `install_systemd_service()` writes.
+#[cfg(target_os = "linux")]
+pub fn unit_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_default();
+    PathBuf::from(home)
+        .join(".config/systemd/user")
+        .join(SERVICE_NAME)
+}
+
+#[cfg(target_os = "linux")]
+fn systemctl(args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() { stdout } else { stderr })
+    }
+}
+
+/// Install and start a systemd user service that runs the installer with `--daemon`.
+#[cfg(target_os = "linux")]
+pub fn install_systemd_service() -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate installer binary: {}", e))?;
+
+    let unit = format!(
+        "[Unit]\n\
+Description=Uprooted hook monitor\n\
+After=graphical-session.target\n\
+PartOf=graphical-session.target\n\
+\n\
+[Service]\n\
+Type=simple\n\
+ExecStart=\"{}\" --daemon\n\
+Restart=on-failure\n\
+\n\
+[Install]\n\
+WantedBy=graphical-session.target\n",
+        exe.display()
+    );
+
+    let path = unit_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, unit).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", SERVICE_NAME])?;
+    Ok(())
+}
+
+/// Stop, disable and delete the monitor service.
+#[cfg(target_os = "linux")]
+pub fn uninstall_systemd_service() -> Result<(), String> {
+    let path = unit_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let _ = systemctl(&["disable", "--now", SERVICE_NAME]);
+    fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    systemctl(&["daemon-reload"])?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_service_status() -> ServiceStatus {
+    // is-active exits non-zero for anything but "active", but still prints the state
+    let state = match systemctl(&["is-active", SERVICE_NAME]) {
+        Ok(s) | Err(s) => s,
+    };
+    ServiceStatus {
+        installed: unit_path().exists(),
+        active: state == "active",
+        state,
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn install_systemd_service() -> Result<(), String> {
+    Err("systemd services are only available on Linux.".to_string())
+}
+
+#[cfg(target_os = "windows")]
+pub fn uninstall_systemd_service() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_service_status() -> ServiceStatus {
+    ServiceStatus {
+        installed: false,
+        active: false,
+        state: "unsupported".to_string(),
+    }
+}
+
+// ==================== Windows: logon entry ====================
+//
+// `schtasks /SC ONLOGON` needs admin rights for most users, so the monitor starts
+// from the per-user Run key instead. The command names still say "scheduled task".
+
+#[cfg(target_os = "windows")]
+pub const TASK_NAME: &str = "UprootedMonitor";
+
+#[cfg(target_os = "windows")]
+pub const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+/// Explain a failed write to the Run key; access is usually denied by Group Policy.
+#[cfg(target_os = "windows")]
+fn run_key_error(action: &str, e: std::io::Error) -> String {
+    use windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED;
+
+    if e.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+        format!(
+            "Failed to {} the logon entry: access to HKCU\\{} was denied. Group Policy \
+             probably blocks startup programs on this machine; run Repair after Root \
+             updates instead, or ask your administrator.",
+            action, RUN_KEY
+        )
+    } else {
+        format!("Failed to {} the logon entry in HKCU\\{}: {}", action, RUN_KEY, e)
+    }
+}
+
+/// Add a logon entry to `HKCU\...\Run` that runs the installer with `--check-repair`.
+#[cfg(target_os = "windows")]
+pub fn install_scheduled_task() -> Result<(), String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate installer binary: {}", e))?;
+    let command = format!("\"{}\" --check-repair", exe.display());
+    let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(RUN_KEY)
+        .map_err(|e| run_key_error("create", e))?;
+    key.set_value(TASK_NAME, &command)
+        .map_err(|e| run_key_error("create", e))
+}
+
+/// Remove the logon entry, and the scheduled task older versions registered.
+#[cfg(target_os = "windows")]
+pub fn remove_scheduled_task() -> Result<(), String> {
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_WRITE};
+    use winreg::RegKey;
+
+    remove_legacy_task();
+    if !is_scheduled_task_installed() {
+        return Ok(());
+    }
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(RUN_KEY, KEY_WRITE)
+        .and_then(|key| key.delete_value(TASK_NAME))
+        .map_err(|e| run_key_error("remove", e))
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_scheduled_task_installed() -> bool {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(RUN_KEY)
+        .and_then(|key| key.get_value::<String, _>(TASK_NAME))
+        .is_ok()
+}
+
+/// Delete the `UprootedMonitor` task if an older version created one.
+#[cfg(target_os = "windows")]
+fn remove_legacy_task() {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let _ = std::process::Command::new("schtasks")
+        .args(["/Delete", "/F", "/TN", TASK_NAME])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+}
+
+#[cfg(target_os = "linux")]
+pub fn install_scheduled_task() -> Result<(), String> {
+    Err("The logon entry is only available on Windows.".to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub fn remove_scheduled_task() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_scheduled_task_installed() -> bool {
+    false
+}
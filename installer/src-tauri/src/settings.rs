@@ -1,4 +1,7 @@
+use crate::css;
 use crate::detection::get_profile_dir;
+use crate::error::UprootedError;
+use crate::plugins;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -7,14 +10,130 @@ use std::fs;
 pub struct PluginSettings {
     pub enabled: bool,
     pub config: HashMap<String, serde_json::Value>,
+    /// Load order of the plugin's stylesheet; lower values are linked first.
+    #[serde(default)]
+    pub priority: i32,
 }
 
+/// Typed access to `config`. Getters return `None` both for missing keys and for
+/// values of another JSON type.
+impl PluginSettings {
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.config.get(key).and_then(|v| v.as_str())
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.config.get(key).and_then(|v| v.as_bool())
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.config.get(key).and_then(|v| v.as_i64())
+    }
+
+    pub fn set_str(&mut self, key: &str, val: &str) {
+        self.config.insert(key.to_string(), serde_json::Value::String(val.to_string()));
+    }
+
+    pub fn set_bool(&mut self, key: &str, val: bool) {
+        self.config.insert(key.to_string(), serde_json::Value::Bool(val));
+    }
+}
+
+/// How the hook is loaded into Root.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HookMode {
+    /// CLR profiler (`CORECLR_*`) which loads the startup hook.
+    #[default]
+    Profiler,
+    /// Only `DOTNET_STARTUP_HOOKS`, for setups where security software flags profilers.
+    StartupHookOnly,
+    /// Profiler env vars and files, but no HTML injection (for users with their own preload).
+    EnvOnlyNoHtml,
+}
+
+/// How the pre-patch backup next to each HTML file is named.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackupNaming {
+    /// A single `index.html.uprooted.bak`, written once.
+    #[default]
+    Simple,
+    /// `index.html.20250101_120000.uprooted.bak`, a new one each time a file is patched.
+    Timestamped,
+}
+
+/// Whose environment the hook env vars are written to.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstallScope {
+    /// The current user only (`HKCU\Environment`).
+    #[default]
+    User,
+    /// Every user on the machine. Windows only; needs administrator rights.
+    System,
+}
+
+/// Contents of the Linux `root-uprooted.desktop` launcher entry.
 #[derive(Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
+pub struct DesktopFileConfig {
+    pub display_name: String,
+    /// `None` uses `icon.png` from the uprooted directory, if there is one.
+    pub icon_path: Option<String>,
+    pub categories: Vec<String>,
+    pub comment: Option<String>,
+}
+
+impl Default for DesktopFileConfig {
+    fn default() -> Self {
+        Self {
+            display_name: "Root (Uprooted)".to_string(),
+            icon_path: None,
+            categories: vec!["Network".to_string(), "Chat".to_string()],
+            comment: Some("Root Communications with Uprooted mods".to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
 pub struct UprootedSettings {
     pub enabled: bool,
     pub plugins: HashMap<String, PluginSettings>,
     pub custom_css: String,
+    /// Minify the theme stylesheet when deploying it.
+    pub minify_css: bool,
+    /// How long a `detect_root` result is reused before rescanning.
+    pub detection_cache_ttl_ms: u64,
+    /// Refuse to deploy DLLs without a valid Authenticode signature (Windows only).
+    /// Off by default until CI signs the release artifacts.
+    pub deploy_verify_signatures: bool,
+    /// Redeploy missing files on launch when the env vars are still set.
+    pub startup_check: bool,
+    /// HTTPS URL of the upstream artifact manifest, signed at `<url>.sig`. Empty disables
+    /// update checks.
+    pub update_check_url: String,
+    /// Extra HTML files patched with `patch_file`, kept patched across repairs.
+    pub custom_targets: Vec<String>,
+    /// Inject a rule that neutralizes Root's own `data-theme` styling.
+    pub disable_root_builtin_theming: bool,
+    /// Which environment variables load the hook. Takes effect on the next install or repair.
+    pub hook_mode: HookMode,
+    /// CLSID the profiler is registered under, for alternate profiler builds.
+    /// `None` uses the built-in GUID.
+    pub profiler_guid: Option<String>,
+    /// Linux only: the app menu entry that launches Root through the wrapper.
+    pub desktop_file: DesktopFileConfig,
+    /// Set after the first successful install, or when the welcome screen is skipped.
+    pub first_run_seen: bool,
+    /// Inject `window.__UPROOTED_META__` with the preload version and the time of patching.
+    /// Off by default: the timestamp makes every repair rewrite the HTML files.
+    pub inject_timing: bool,
+    /// Install for the current user or machine-wide. A system install elevates through UAC.
+    pub install_scope: InstallScope,
+    /// Keep one backup per file, or a timestamped one per patch for versioned history.
+    pub backup_naming: BackupNaming,
+    /// Add `file: blob: data:` to a page's Content-Security-Policy when it would block
+    /// the injected script and stylesheets. Uninstall puts the original policy back.
+    pub fix_csp: bool,
 }
 
 impl Default for UprootedSettings {
@@ -23,12 +142,36 @@ impl Default for UprootedSettings {
             enabled: true,
             plugins: HashMap::new(),
             custom_css: String::new(),
+            minify_css: true,
+            detection_cache_ttl_ms: 5000,
+            deploy_verify_signatures: false,
+            startup_check: true,
+            update_check_url: String::new(),
+            custom_targets: Vec::new(),
+            disable_root_builtin_theming: false,
+            hook_mode: HookMode::Profiler,
+            profiler_guid: None,
+            desktop_file: DesktopFileConfig::default(),
+            first_run_seen: false,
+            inject_timing: false,
+            install_scope: InstallScope::User,
+            backup_naming: BackupNaming::Simple,
+            fix_csp: false,
         }
     }
 }
 
-fn settings_path() -> std::path::PathBuf {
-    get_profile_dir().join("uprooted-settings.json")
+/// `uprooted-settings.json` in the profile. `UPROOTED_SETTINGS_PATH` overrides it
+/// (development and CI only).
+pub fn settings_path() -> std::path::PathBuf {
+    settings_override_path().unwrap_or_else(|| get_profile_dir().join("uprooted-settings.json"))
+}
+
+/// The `UPROOTED_SETTINGS_PATH` override, if set and non-empty.
+pub fn settings_override_path() -> Option<std::path::PathBuf> {
+    std::env::var_os("UPROOTED_SETTINGS_PATH")
+        .filter(|path| !path.is_empty())
+        .map(Into::into)
 }
 
 pub fn load_settings() -> UprootedSettings {
@@ -44,6 +187,17 @@ pub fn load_settings() -> UprootedSettings {
 }
 
 pub fn save_settings(settings: &UprootedSettings) -> Result<(), String> {
+    if !settings.custom_css.trim().is_empty() {
+        if let Err(errors) = css::validate_css(&settings.custom_css) {
+            return Err(serde_json::to_string(&errors)
+                .unwrap_or_else(|_| "Custom CSS is invalid".to_string()));
+        }
+    }
+
+    if let Some(guid) = &settings.profiler_guid {
+        validate_profiler_guid(guid)?;
+    }
+
     let path = settings_path();
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
@@ -52,3 +206,186 @@ pub fn save_settings(settings: &UprootedSettings) -> Result<(), String> {
         serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize: {}", e))?;
     fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))
 }
+
+/// Set one value by JSON Pointer (e.g. `/plugins/themes/config/theme`) and save,
+/// leaving every other field as it is on disk. The last path segment may be a new
+/// key in an existing object.
+pub fn save_settings_field(field_path: &str, value: serde_json::Value) -> Result<(), String> {
+    let invalid = || format!("Invalid settings path: {}", field_path);
+    let (parent, key) = field_path.rsplit_once('/').ok_or_else(invalid)?;
+    let key = key.replace("~1", "/").replace("~0", "~");
+
+    let current = load_settings();
+    let mut json =
+        serde_json::to_value(&current).map_err(|e| format!("Failed to serialize: {}", e))?;
+    let existed = json.pointer(field_path).is_some();
+    match json.pointer_mut(parent) {
+        Some(serde_json::Value::Object(map)) => {
+            map.insert(key, value.clone());
+        }
+        Some(serde_json::Value::Array(items)) => {
+            let slot = key
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get_mut(i))
+                .ok_or_else(invalid)?;
+            *slot = value.clone();
+        }
+        _ => return Err(invalid()),
+    }
+
+    let settings: UprootedSettings = serde_json::from_value(json)
+        .map_err(|e| format!("Invalid value for {}: {}", field_path, e))?;
+    // Unknown struct fields are dropped on deserialize rather than rejected; a new key
+    // in a map field survives the round trip.
+    if !existed {
+        let saved =
+            serde_json::to_value(&settings).map_err(|e| format!("Failed to serialize: {}", e))?;
+        if saved.pointer(field_path).is_none() {
+            return Err(format!("Unknown setting: {}", field_path));
+        }
+    }
+    plugins::validate_changed_plugin_configs(&current, &settings)?;
+    save_settings(&settings)
+}
+
+/// Fails with `UprootedError::Disabled` when the global `enabled` flag is off.
+/// Install, repair and env var changes check this first and skip their work.
+pub fn assert_enabled(settings: &UprootedSettings) -> Result<(), UprootedError> {
+    if settings.enabled {
+        Ok(())
+    } else {
+        Err(UprootedError::Disabled)
+    }
+}
+
+/// Check that `guid` is a braced CLSID, e.g. `{D1A6F5A0-1234-4567-89AB-CDEF01234567}`.
+pub fn validate_profiler_guid(guid: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid profiler GUID: {}", guid);
+    let inner = guid
+        .strip_prefix('{')
+        .and_then(|g| g.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+    let groups: Vec<&str> = inner.split('-').collect();
+    let lengths = [8, 4, 4, 4, 12];
+    if groups.len() != lengths.len()
+        || groups
+            .iter()
+            .zip(lengths)
+            .any(|(g, len)| g.len() != len || !g.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct DiffEntry {
+    pub path: String,
+    pub default_value: serde_json::Value,
+    pub current_value: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct SettingsDiff {
+    pub changed_fields: Vec<DiffEntry>,
+}
+
+fn diff_values(
+    path: &str,
+    default: &serde_json::Value,
+    current: &serde_json::Value,
+    out: &mut Vec<DiffEntry>,
+) {
+    use serde_json::Value;
+
+    if let (Value::Object(d), Value::Object(c)) = (default, current) {
+        let mut keys: Vec<&String> = d.keys().chain(c.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            diff_values(
+                &child,
+                d.get(key).unwrap_or(&Value::Null),
+                c.get(key).unwrap_or(&Value::Null),
+                out,
+            );
+        }
+    } else if default != current {
+        out.push(DiffEntry {
+            path: path.to_string(),
+            default_value: default.clone(),
+            current_value: current.clone(),
+        });
+    }
+}
+
+/// Every leaf of the current settings that differs from `UprootedSettings::default()`,
+/// addressed by dot-separated path (e.g. `plugins.themes.config.theme`).
+pub fn get_settings_diff() -> SettingsDiff {
+    let default = serde_json::to_value(UprootedSettings::default()).unwrap_or_default();
+    let current = serde_json::to_value(load_settings()).unwrap_or_default();
+    let mut changed_fields = Vec::new();
+    diff_values("", &default, &current, &mut changed_fields);
+    SettingsDiff { changed_fields }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plugin(config: serde_json::Value) -> PluginSettings {
+        serde_json::from_value(json!({ "enabled": true, "config": config })).unwrap()
+    }
+
+    #[test]
+    fn get_str_rejects_other_types() {
+        let p = plugin(json!({ "theme": "crimson", "count": 3, "flag": true, "none": null }));
+        assert_eq!(p.get_str("theme"), Some("crimson"));
+        assert_eq!(p.get_str("count"), None);
+        assert_eq!(p.get_str("flag"), None);
+        assert_eq!(p.get_str("none"), None);
+        assert_eq!(p.get_str("missing"), None);
+    }
+
+    #[test]
+    fn get_bool_rejects_other_types() {
+        let p = plugin(json!({ "flag": false, "text": "true", "number": 1 }));
+        assert_eq!(p.get_bool("flag"), Some(false));
+        assert_eq!(p.get_bool("text"), None);
+        assert_eq!(p.get_bool("number"), None);
+        assert_eq!(p.get_bool("missing"), None);
+    }
+
+    #[test]
+    fn get_i64_rejects_other_types() {
+        let p = plugin(json!({ "count": -42, "float": 1.5, "text": "7", "huge": u64::MAX }));
+        assert_eq!(p.get_i64("count"), Some(-42));
+        assert_eq!(p.get_i64("float"), None);
+        assert_eq!(p.get_i64("text"), None);
+        assert_eq!(p.get_i64("huge"), None);
+        assert_eq!(p.get_i64("missing"), None);
+    }
+
+    #[test]
+    fn set_str_overwrites_value_of_another_type() {
+        let mut p = plugin(json!({ "theme": 5 }));
+        p.set_str("theme", "sapphire");
+        assert_eq!(p.get_str("theme"), Some("sapphire"));
+        assert_eq!(p.get_i64("theme"), None);
+    }
+
+    #[test]
+    fn set_bool_overwrites_value_of_another_type() {
+        let mut p = plugin(json!({ "compact": "yes" }));
+        p.set_bool("compact", true);
+        assert_eq!(p.get_bool("compact"), Some(true));
+        assert_eq!(p.get_str("compact"), None);
+    }
+}
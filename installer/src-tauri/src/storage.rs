@@ -0,0 +1,326 @@
+use crate::detection::get_profile_dir;
+use crate::error::UprootedError;
+use crate::hook;
+use crate::logging;
+use crate::settings;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct DiskUsage {
+    pub uprooted_dir_bytes: u64,
+    pub backup_files_bytes: u64,
+    /// No profile snapshots are taken yet, so this is always 0.
+    pub profile_snapshot_bytes: u64,
+    pub log_files_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Total size of all files under `path`. Missing or unreadable entries count as 0.
+pub fn directory_size(path: &Path) -> u64 {
+    let entries = match fs::read_dir(path) {
+        Ok(e) => e,
+        Err(_) => return 0,
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => directory_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn is_backup_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    name.ends_with(".uprooted.bak") || name.ends_with(".uprooted.bak.gz")
+}
+
+/// Every `*.uprooted.bak` / `*.uprooted.bak.gz` file under `dir`, timestamped ones included.
+pub fn find_backup_files(dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return found,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => found.extend(find_backup_files(&path)),
+            Ok(t) if t.is_file() && is_backup_file(&path) => found.push(path),
+            _ => {}
+        }
+    }
+    found
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Installer and hook logs in the profile directory.
+pub fn log_files() -> Vec<PathBuf> {
+    vec![logging::log_path(), get_profile_dir().join("uprooted-hook.log")]
+}
+
+/// Bytes available to the current user on the filesystem holding `path`.
+#[cfg(target_os = "linux")]
+#[allow(clippy::unnecessary_cast)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(target_os = "windows")]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut free: u64 = 0;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(wide.as_ptr(), &mut free, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    (ok != 0).then_some(free)
+}
+
+/// Probe the profile and uprooted directories. Install writes backups to the profile
+/// first, and a bare "Failed to backup" doesn't tell the user why.
+pub fn validate_profile_writable() -> Result<(), UprootedError> {
+    probe_writable(&get_profile_dir())?;
+    probe_writable(&hook::get_uprooted_dir())
+}
+
+/// Write, read back and delete a probe file in `dir`, or in its nearest existing
+/// ancestor if it hasn't been created yet.
+fn probe_writable(dir: &Path) -> Result<(), UprootedError> {
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        return Err(UprootedError::Io(format!("{} has no existing parent", dir.display())));
+    };
+
+    let probe = existing.join(".uprooted-write-test");
+    let result = fs::write(&probe, [1u8]).and_then(|_| fs::read(&probe)).and_then(|data| {
+        if data == [1u8] {
+            Ok(())
+        } else {
+            Err(std::io::Error::other("test file read back with different contents"))
+        }
+    });
+    let _ = fs::remove_file(&probe);
+
+    result.map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => UprootedError::Io(format!(
+            "No permission to write to {}. Check that the folder isn't read-only \
+             or owned by another user.{}",
+            existing.display(),
+            fs_type_hint(existing)
+        )),
+        std::io::ErrorKind::StorageFull => UprootedError::Io(format!(
+            "The disk holding {} is full ({} MB free). Free up some space and try again.",
+            existing.display(),
+            available_space(existing).unwrap_or(0) / (1024 * 1024)
+        )),
+        _ => UprootedError::Io(format!(
+            "Cannot write to {}: {}.{}",
+            existing.display(),
+            e,
+            fs_type_hint(existing)
+        )),
+    })
+}
+
+/// Advice for a write that failed on the file system holding `path`. A read-only home,
+/// such as an NFS mount with root_squash, passes permission checks but fails the probe.
+fn fs_type_hint(path: &Path) -> String {
+    match filesystem_type(path).as_deref() {
+        Some("nfs") => " It is on an NFS mount, which may be exported read-only or with \
+             root_squash; ask the administrator to make it writable for your user."
+            .to_string(),
+        Some("tmpfs") => " It is on tmpfs, which may be mounted read-only; anything written \
+             there would also be lost on reboot."
+            .to_string(),
+        Some(other) => format!(" The file system is {}.", other),
+        None => String::new(),
+    }
+}
+
+/// Whether the current user may write to `path`, or to its nearest existing ancestor
+/// if it hasn't been created yet. Nothing is written.
+#[cfg(target_os = "linux")]
+fn can_write(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return false;
+    };
+    let Ok(c_path) = std::ffi::CString::new(existing.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 }
+}
+
+/// Windows has no cheap access check without opening the directory for writing, so
+/// this only looks at the read-only attribute.
+#[cfg(target_os = "windows")]
+fn can_write(path: &Path) -> bool {
+    path.ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| fs::metadata(p).ok())
+        .is_some_and(|m| !m.permissions().readonly())
+}
+
+/// Name of the filesystem holding `path`, from its `statfs` magic number.
+#[cfg(target_os = "linux")]
+#[allow(clippy::unnecessary_cast)]
+fn filesystem_type(path: &Path) -> Option<String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let name = match stat.f_type as u32 {
+        0x6969 => "nfs",
+        0x0102_1994 => "tmpfs",
+        0xFF53_4D42 => "cifs",
+        0xFE53_4D42 => "smb2",
+        0x6573_5546 => "fuse",
+        0x794C_7630 => "overlayfs",
+        0x7371_7368 => "squashfs",
+        0x0000_EF53 => "ext4",
+        0x9123_683E => "btrfs",
+        0x5846_5342 => "xfs",
+        0x2FC1_2FC1 => "zfs",
+        other => return Some(format!("unknown (0x{:x})", other)),
+    };
+    Some(name.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn filesystem_type(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Fail with the first directory the current user can't write, e.g. when uprooted
+/// was installed by another user. Check commands still work in that case.
+pub fn assert_writable() -> Result<(), UprootedError> {
+    for dir in [hook::get_uprooted_dir(), get_profile_dir()] {
+        if !can_write(&dir) {
+            return Err(UprootedError::ReadOnly(dir.display().to_string()));
+        }
+    }
+    Ok(())
+}
+
+pub fn is_read_only_mode() -> bool {
+    assert_writable().is_err()
+}
+
+pub fn get_disk_usage() -> DiskUsage {
+    let uprooted_dir_bytes = directory_size(&hook::get_uprooted_dir());
+    let backup_files_bytes = find_backup_files(&get_profile_dir())
+        .iter()
+        .map(|p| file_size(p))
+        .sum();
+    let log_files_bytes = log_files().iter().map(|p| file_size(p)).sum();
+    let profile_snapshot_bytes = 0;
+
+    DiskUsage {
+        uprooted_dir_bytes,
+        backup_files_bytes,
+        profile_snapshot_bytes,
+        log_files_bytes,
+        total_bytes: uprooted_dir_bytes + backup_files_bytes + profile_snapshot_bytes + log_files_bytes,
+    }
+}
+
+/// Contents of each file, `None` where it didn't exist.
+pub type FileSnapshot = Vec<(PathBuf, Option<Vec<u8>>)>;
+
+/// Read `paths` for `restore_snapshot()`.
+pub fn snapshot_files(paths: impl IntoIterator<Item = PathBuf>) -> FileSnapshot {
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read(&path).ok();
+            (path, content)
+        })
+        .collect()
+}
+
+/// Write each file back as it was, deleting the ones that didn't exist.
+pub fn restore_snapshot(snapshot: &FileSnapshot) -> Result<(), String> {
+    for (path, content) in snapshot {
+        let restored = match content {
+            Some(data) => fs::write(path, data),
+            None => match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        };
+        restored.map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Default)]
+pub struct PurgeReport {
+    pub steps_completed: Vec<String>,
+    pub errors: Vec<String>,
+    pub bytes_freed: u64,
+}
+
+/// Bytes a purge would reclaim: install directory, HTML backups and the settings file.
+pub fn purgeable_bytes() -> u64 {
+    let usage = get_disk_usage();
+    usage.uprooted_dir_bytes + usage.backup_files_bytes + file_size(&settings::settings_path())
+}
+
+/// Remove everything uprooted leaves behind once the regular uninstall has run:
+/// HTML backups, the install directory, settings and any remaining env var mechanisms.
+pub fn purge_leftovers(report: &mut PurgeReport) {
+    let mut backup_errors = 0;
+    for backup in find_backup_files(&get_profile_dir()) {
+        if let Err(e) = fs::remove_file(&backup) {
+            backup_errors += 1;
+            report.errors.push(format!("Failed to remove {}: {}", backup.display(), e));
+        }
+    }
+    if backup_errors == 0 {
+        report.steps_completed.push("Removed HTML backups".to_string());
+    }
+
+    match hook::remove_files() {
+        Ok(()) => report.steps_completed.push("Removed uprooted directory".to_string()),
+        Err(e) => report.errors.push(e),
+    }
+
+    let settings = settings::settings_path();
+    match fs::remove_file(&settings) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => report
+            .errors
+            .push(format!("Failed to remove {}: {}", settings.display(), e)),
+        _ => report.steps_completed.push("Removed settings file".to_string()),
+    }
+
+    // Covers environment.d + .desktop on Linux and all HKCU env vars on Windows.
+    // Run again in case uninstall bailed out before reaching it.
+    match hook::remove_env_vars() {
+        Ok(()) => report.steps_completed.push("Removed environment variables".to_string()),
+        Err(e) => report.errors.push(e),
+    }
+}
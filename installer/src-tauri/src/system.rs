@@ -0,0 +1,91 @@
+use crate::compat;
+use crate::detection;
+use crate::embedded;
+use crate::hook;
+use serde::Serialize;
+
+pub const DOTNET_DOWNLOAD_URL: &str = "https://dotnet.microsoft.com/download";
+
+#[derive(Serialize)]
+pub struct SystemInfo {
+    pub os: String,
+    pub arch: String,
+    pub root_version: Option<String>,
+    pub dotnet_version: Option<String>,
+    pub is_snap: bool,
+    pub in_wsl: bool,
+}
+
+pub fn get_system_info() -> SystemInfo {
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: hook::native_arch().to_string(),
+        root_version: detection::get_root_version(),
+        dotnet_version: detect_dotnet_version(),
+        is_snap: detection::is_snap_install(),
+        in_wsl: detection::is_running_in_wsl(),
+    }
+}
+
+/// Installed .NET host version, from the registry or the shared runtime folder.
+#[cfg(target_os = "windows")]
+pub fn detect_dotnet_version() -> Option<String> {
+    use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ, KEY_WOW64_64KEY};
+    use winreg::RegKey;
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    if let Ok(key) = hklm.open_subkey_with_flags(
+        "SOFTWARE\\dotnet\\Setup\\InstalledVersions\\x64\\sharedhost",
+        KEY_READ | KEY_WOW64_64KEY,
+    ) {
+        if let Ok(version) = key.get_value::<String, _>("Version") {
+            return Some(version);
+        }
+    }
+
+    let program_files = std::env::var("ProgramFiles").unwrap_or_else(|_| "C:\\Program Files".to_string());
+    let shared = std::path::PathBuf::from(program_files).join("dotnet\\shared\\Microsoft.NETCore.App");
+    std::fs::read_dir(shared)
+        .ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|v| compat::parse_version(v).is_some())
+        .max_by_key(|v| compat::parse_version(v))
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_dotnet_version() -> Option<String> {
+    let output = std::process::Command::new("dotnet").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if version.is_empty() {
+        None
+    } else {
+        Some(version)
+    }
+}
+
+/// .NET version `UprootedHook.dll` targets, read from the embedded `deps.json`
+/// (`"runtimeTarget": { "name": ".NETCoreApp,Version=v8.0" }`).
+pub fn required_dotnet_version() -> Option<String> {
+    let deps_json = embedded::decompress(embedded::HOOK_DEPS_JSON).ok()?;
+    let deps: serde_json::Value = serde_json::from_slice(&deps_json).ok()?;
+    let target = deps.get("runtimeTarget")?.get("name")?.as_str()?;
+    let version = target.split("Version=v").nth(1)?;
+    Some(version.to_string())
+}
+
+/// Explain why the installed .NET is too old, or `None` if it is new enough or unknown.
+pub fn check_dotnet_version(installed: Option<&str>) -> Option<String> {
+    let installed = installed?;
+    let required = required_dotnet_version()?;
+    if compat::parse_version(installed)? >= compat::parse_version(&required)? {
+        return None;
+    }
+    Some(format!(
+        ".NET {} is installed, but Uprooted's hook needs .NET {} or newer. Download it from {}",
+        installed, required, DOTNET_DOWNLOAD_URL
+    ))
+}
@@ -1,5 +1,33 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::hook;
+use crate::updates;
+
+/// Largest theme file accepted by [`import_theme_from_url`].
+const MAX_THEME_DOWNLOAD_BYTES: u64 = 1024 * 1024;
+
+/// Largest uncompressed `theme.json` accepted from a theme package.
+const MAX_THEME_JSON_BYTES: u64 = 1024 * 1024;
+
+/// Largest total uncompressed size of a theme package's assets.
+const MAX_THEME_ASSETS_BYTES: u64 = 32 * 1024 * 1024;
+
+/// File types allowed in a theme package's `assets/` directory.
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "svg", "woff", "woff2", "ttf", "otf",
+];
+
+/// How many `$name` references may be followed from one variable.
+const MAX_REFERENCE_DEPTH: usize = 5;
+
+/// WCAG AA minimum for normal body text.
+pub const WCAG_AA_TEXT: f64 = 4.5;
+/// WCAG AA minimum for large text and UI components.
+pub const WCAG_AA_UI: f64 = 3.0;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PreviewColors {
@@ -15,11 +43,651 @@ pub struct ThemeDefinition {
     pub display_name: String,
     pub description: String,
     pub author: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Name of a theme whose variables this one inherits and overrides.
+    #[serde(default)]
+    pub extends: Option<String>,
     pub variables: HashMap<String, String>,
     pub preview_colors: PreviewColors,
 }
 
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ThemeQuery {
+    pub search: Option<String>,
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ContrastWarning {
+    pub pair: (String, String),
+    pub ratio: f64,
+    pub required: f64,
+}
+
+#[derive(Serialize, Default)]
+pub struct PreviewColorsDiff {
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Serialize, Default)]
+pub struct ThemeDiff {
+    pub only_in_a: HashMap<String, String>,
+    pub only_in_b: HashMap<String, String>,
+    pub changed: HashMap<String, (String, String)>,
+    pub identical_count: u32,
+    pub preview_diff: PreviewColorsDiff,
+}
+
+#[derive(Serialize)]
+pub struct ImportResult {
+    pub theme: ThemeDefinition,
+    pub warnings: Vec<ContrastWarning>,
+}
+
 pub fn get_builtin_themes() -> Vec<ThemeDefinition> {
     let json = include_str!("../../../src/plugins/themes/themes.json");
     serde_json::from_str(json).unwrap_or_default()
 }
+
+fn user_themes_dir() -> PathBuf {
+    hook::get_uprooted_dir().join("themes")
+}
+
+/// Themes previously saved with [`import_theme`]. Unreadable files are skipped.
+pub fn get_user_themes() -> Vec<ThemeDefinition> {
+    let Ok(entries) = fs::read_dir(user_themes_dir()) else {
+        return Vec::new();
+    };
+    let mut themes: Vec<ThemeDefinition> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+pub fn get_all_themes() -> Vec<ThemeDefinition> {
+    let mut themes = get_builtin_themes();
+    themes.extend(get_user_themes());
+    themes
+}
+
+/// `name` with the variables of every theme up its `extends` chain merged in;
+/// the nearest definition of a variable wins.
+pub fn resolve_theme(name: &str, themes: &[ThemeDefinition]) -> Result<ThemeDefinition, String> {
+    let find = |n: &str| {
+        themes
+            .iter()
+            .find(|t| t.name == n)
+            .ok_or_else(|| format!("Theme '{}' not found", n))
+    };
+
+    let mut resolved = find(name)?.clone();
+    let mut seen = vec![resolved.name.clone()];
+    let mut parent = resolved.extends.clone();
+    while let Some(parent_name) = parent {
+        if seen.contains(&parent_name) {
+            return Err(format!(
+                "Theme '{}' has an inheritance cycle at '{}'",
+                name, parent_name
+            ));
+        }
+        let base = find(&parent_name)?;
+        for (key, value) in &base.variables {
+            resolved
+                .variables
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        seen.push(parent_name);
+        parent = base.extends.clone();
+    }
+    resolved.variables = resolve_variable_references(&resolved.variables)?;
+    Ok(resolved)
+}
+
+/// Expand `$name` in variable values to the value of `--name`, e.g.
+/// `"--background-hover": "$background"`. References may nest up to
+/// `MAX_REFERENCE_DEPTH` levels; cycles and unknown names are errors.
+pub fn resolve_variable_references(
+    vars: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    vars.iter()
+        .map(|(key, value)| {
+            let mut chain = vec![key.clone()];
+            expand_references(value, vars, &mut chain).map(|v| (key.clone(), v))
+        })
+        .collect()
+}
+
+fn expand_references(
+    value: &str,
+    vars: &HashMap<String, String>,
+    chain: &mut Vec<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(after.len());
+        if len == 0 {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+
+        let name = format!("--{}", &after[..len]);
+        if chain.contains(&name) {
+            return Err(format!(
+                "Variable reference cycle: {} -> {}",
+                chain.join(" -> "),
+                name
+            ));
+        }
+        if chain.len() > MAX_REFERENCE_DEPTH {
+            return Err(format!(
+                "Variable {} nests references more than {} levels deep",
+                chain[0], MAX_REFERENCE_DEPTH
+            ));
+        }
+        let Some(target) = vars.get(&name) else {
+            let from = &chain[chain.len() - 1];
+            return Err(format!("{} references unknown variable {}", from, name));
+        };
+        chain.push(name);
+        out.push_str(&expand_references(target, vars, chain)?);
+        chain.pop();
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Variable-level differences between two themes after resolving inheritance.
+pub fn compare_themes(name_a: &str, name_b: &str) -> Result<ThemeDiff, String> {
+    let themes = get_all_themes();
+    let a = resolve_theme(name_a, &themes)?;
+    let b = resolve_theme(name_b, &themes)?;
+
+    let mut diff = ThemeDiff::default();
+    for (key, value_a) in &a.variables {
+        match b.variables.get(key) {
+            None => {
+                diff.only_in_a.insert(key.clone(), value_a.clone());
+            }
+            Some(value_b) if !value_a.eq_ignore_ascii_case(value_b) => {
+                diff.changed
+                    .insert(key.clone(), (value_a.clone(), value_b.clone()));
+            }
+            Some(_) => diff.identical_count += 1,
+        }
+    }
+    for (key, value_b) in &b.variables {
+        if !a.variables.contains_key(key) {
+            diff.only_in_b.insert(key.clone(), value_b.clone());
+        }
+    }
+
+    let (pa, pb) = (&a.preview_colors, &b.preview_colors);
+    diff.preview_diff.changed_fields = [
+        ("background", &pa.background, &pb.background),
+        ("text", &pa.text, &pb.text),
+        ("accent", &pa.accent, &pb.accent),
+        ("border", &pa.border, &pb.border),
+    ]
+    .into_iter()
+    .filter(|(_, x, y)| !x.eq_ignore_ascii_case(y))
+    .map(|(field, _, _)| field.to_string())
+    .collect();
+
+    Ok(diff)
+}
+
+/// Themes matching every part of `query`. Search is a case-insensitive substring match
+/// on name, display name, description and author; tags must all be present.
+pub fn filter_themes(query: &ThemeQuery) -> Vec<ThemeDefinition> {
+    let search = query
+        .search
+        .as_deref()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+
+    get_all_themes()
+        .into_iter()
+        .filter(|t| {
+            search.as_ref().is_none_or(|q| {
+                [&t.name, &t.display_name, &t.description, &t.author]
+                    .iter()
+                    .any(|field| field.to_lowercase().contains(q.as_str()))
+            })
+        })
+        .filter(|t| {
+            query
+                .tags
+                .iter()
+                .all(|tag| t.tags.iter().any(|own| own.eq_ignore_ascii_case(tag)))
+        })
+        .filter(|t| {
+            query.category.as_ref().is_none_or(|c| {
+                t.category
+                    .as_ref()
+                    .is_some_and(|own| own.eq_ignore_ascii_case(c))
+            })
+        })
+        .collect()
+}
+
+fn is_valid_theme_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Structural checks for a theme that is about to be saved.
+pub fn validate_theme(theme: &ThemeDefinition) -> Result<(), String> {
+    if !is_valid_theme_name(&theme.name) {
+        return Err(format!(
+            "Invalid theme name '{}': use letters, digits, '-' or '_'",
+            theme.name
+        ));
+    }
+    for tag in &theme.tags {
+        if tag.is_empty() || tag.chars().any(char::is_whitespace) {
+            return Err(format!("Invalid tag '{}': tags must be non-empty with no spaces", tag));
+        }
+    }
+    for (key, value) in theme.variables.iter() {
+        if !key.starts_with("--") {
+            return Err(format!("Invalid variable name '{}'", key));
+        }
+        if value.contains([';', '{', '}', '<']) {
+            return Err(format!("Invalid value for {}", key));
+        }
+    }
+    Ok(())
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG 2.x contrast ratio between two hex colors, from 1.0 to 21.0.
+/// Colors that fail to parse are treated as having no contrast.
+pub fn wcag_contrast_ratio(fg: &str, bg: &str) -> f64 {
+    let (Ok(fg), Ok(bg)) = (parse_hex(fg), parse_hex(bg)) else {
+        return 1.0;
+    };
+    let (a, b) = (relative_luminance(fg), relative_luminance(bg));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+pub fn validate_theme_contrast(theme: &ThemeDefinition) -> Vec<ContrastWarning> {
+    let p = &theme.preview_colors;
+    [
+        ("text", &p.text, "background", &p.background, WCAG_AA_TEXT),
+        ("accent", &p.accent, "background", &p.background, WCAG_AA_UI),
+    ]
+    .into_iter()
+    .filter_map(|(fg_name, fg, bg_name, bg, required)| {
+        let ratio = wcag_contrast_ratio(fg, bg);
+        (ratio < required).then(|| ContrastWarning {
+            pair: (fg_name.to_string(), bg_name.to_string()),
+            ratio: (ratio * 100.0).round() / 100.0,
+            required,
+        })
+    })
+    .collect()
+}
+
+/// Parse a theme definition and save it alongside the built-in themes.
+/// Contrast problems are reported but do not block the import.
+pub fn import_theme(json: &str) -> Result<ImportResult, String> {
+    let theme: ThemeDefinition =
+        serde_json::from_str(json).map_err(|e| format!("Invalid theme file: {}", e))?;
+    check_importable(&theme)?;
+    save_user_theme(theme)
+}
+
+/// Download a theme definition over HTTPS and save it once it validates.
+pub fn import_theme_from_url(
+    url: &str,
+    on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<ThemeDefinition, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if parsed.scheme() != "https" {
+        return Err(format!("Only https:// URLs are allowed, got {}://", parsed.scheme()));
+    }
+
+    let body = updates::download_with_progress(parsed.as_str(), MAX_THEME_DOWNLOAD_BYTES, on_progress)?;
+    let theme: ThemeDefinition =
+        serde_json::from_slice(&body).map_err(|e| format!("Invalid theme file: {}", e))?;
+    check_importable(&theme)?;
+
+    let result = save_user_theme(theme)?;
+    for w in &result.warnings {
+        tracing::warn!(
+            "theme {}: {}/{} contrast {}:1 below {}:1",
+            result.theme.name, w.pair.0, w.pair.1, w.ratio, w.required
+        );
+    }
+    Ok(result.theme)
+}
+
+/// Import a theme package: a zip with `theme.json` at its root and an optional
+/// `assets/` directory of images and fonts. `url(assets/...)` references in the
+/// theme's variables are rewritten to `file://` URLs of the extracted files.
+pub fn import_theme_zip(path: &Path) -> Result<ImportResult, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid theme package: {}", e))?;
+
+    let mut json = String::new();
+    archive
+        .by_name("theme.json")
+        .map_err(|_| "Theme package has no theme.json".to_string())?
+        .take(MAX_THEME_JSON_BYTES + 1)
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read theme.json: {}", e))?;
+    if json.len() as u64 > MAX_THEME_JSON_BYTES {
+        return Err(format!("theme.json is larger than {} bytes", MAX_THEME_JSON_BYTES));
+    }
+    let mut theme: ThemeDefinition =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid theme file: {}", e))?;
+    check_importable(&theme)?;
+
+    // Extract next to the final location and swap it in only once everything succeeded,
+    // so a bad package leaves the previously imported assets untouched.
+    let theme_dir = user_themes_dir().join(&theme.name);
+    let assets_dir = theme_dir.join("assets");
+    let staging_dir = user_themes_dir().join(format!(".{}.importing", theme.name));
+    if staging_dir.exists() {
+        let _ = fs::remove_dir_all(&staging_dir);
+    }
+    let extracted = extract_theme_assets(&mut archive, &staging_dir);
+    if let Err(e) = extracted {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+    if theme_dir.exists() {
+        fs::remove_dir_all(&theme_dir).map_err(|e| format!("Failed to replace old assets: {}", e))?;
+    }
+    if staging_dir.exists() {
+        fs::rename(&staging_dir, &theme_dir)
+            .map_err(|e| format!("Failed to move assets into place: {}", e))?;
+    }
+
+    for value in theme.variables.values_mut() {
+        *value = rewrite_asset_urls(value, &assets_dir);
+    }
+    save_user_theme(theme)
+}
+
+/// Extract the `assets/` entries of a theme package into `dest`, stopping once their
+/// uncompressed size passes `MAX_THEME_ASSETS_BYTES`.
+fn extract_theme_assets(archive: &mut zip::ZipArchive<fs::File>, dest: &Path) -> Result<(), String> {
+    let mut remaining = MAX_THEME_ASSETS_BYTES;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Invalid theme package: {}", e))?;
+        let Some(rel) = entry.enclosed_name() else {
+            return Err(format!("Refusing unsafe path in package: {}", entry.name()));
+        };
+        if entry.is_dir() || !rel.starts_with("assets") {
+            continue;
+        }
+        if !is_allowed_asset(&rel) {
+            return Err(format!(
+                "Unsupported asset type: {} (only images and fonts are allowed)",
+                rel.display()
+            ));
+        }
+        if entry.size() > remaining {
+            return Err(format!(
+                "Theme assets are larger than {} MB",
+                MAX_THEME_ASSETS_BYTES / (1024 * 1024)
+            ));
+        }
+        let path = dest.join(&rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        let mut out = fs::File::create(&path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        // The header size can lie; count what is actually decompressed.
+        let written = io::copy(&mut entry.take(remaining + 1), &mut out)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        if written > remaining {
+            return Err(format!(
+                "Theme assets are larger than {} MB",
+                MAX_THEME_ASSETS_BYTES / (1024 * 1024)
+            ));
+        }
+        remaining -= written;
+    }
+    Ok(())
+}
+
+/// Absolute path of an asset shipped with an imported theme package.
+pub fn theme_asset_path(theme_name: &str, asset: &str) -> Result<String, String> {
+    if !is_valid_theme_name(theme_name) {
+        return Err(format!("Invalid theme name '{}'", theme_name));
+    }
+    let rel = Path::new(asset);
+    if rel.components().any(|c| !matches!(c, Component::Normal(_))) || !is_allowed_asset(rel) {
+        return Err(format!("Invalid asset name '{}'", asset));
+    }
+    let path = user_themes_dir().join(theme_name).join("assets").join(rel);
+    if !path.is_file() {
+        return Err(format!("Asset not found: {}", asset));
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn check_importable(theme: &ThemeDefinition) -> Result<(), String> {
+    validate_theme(theme)?;
+    if get_builtin_themes().iter().any(|t| t.name == theme.name) {
+        return Err(format!("'{}' is a built-in theme name", theme.name));
+    }
+    if let Some(parent) = &theme.extends {
+        if !get_all_themes().iter().any(|t| &t.name == parent) {
+            return Err(format!("Theme '{}' extends unknown theme '{}'", theme.name, parent));
+        }
+    }
+    Ok(())
+}
+
+fn save_user_theme(theme: ThemeDefinition) -> Result<ImportResult, String> {
+    let dir = user_themes_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let out = serde_json::to_string_pretty(&theme)
+        .map_err(|e| format!("Failed to serialize: {}", e))?;
+    fs::write(dir.join(format!("{}.json", theme.name)), out)
+        .map_err(|e| format!("Failed to save theme: {}", e))?;
+
+    let warnings = validate_theme_contrast(&theme);
+    Ok(ImportResult { theme, warnings })
+}
+
+fn is_allowed_asset(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ASSET_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+fn file_url(path: &Path) -> String {
+    let p = path.to_string_lossy().replace('\\', "/");
+    if p.starts_with('/') {
+        format!("file://{}", p)
+    } else {
+        format!("file:///{}", p)
+    }
+}
+
+/// Replace `url(assets/x)` (optionally quoted or prefixed with `./`) with a `file://` URL.
+fn rewrite_asset_urls(value: &str, assets_dir: &Path) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("url(") {
+        let (before, after) = rest.split_at(start + 4);
+        out.push_str(before);
+        let Some(end) = after.find(')') else {
+            rest = after;
+            break;
+        };
+        let target = after[..end].trim().trim_matches(|c| c == '"' || c == '\'');
+        let local = target.strip_prefix("./").unwrap_or(target);
+        match local.strip_prefix("assets/") {
+            Some(name) if !name.split('/').any(|seg| seg == ".." || seg.is_empty()) => {
+                out.push_str(&format!("\"{}\"", file_url(&assets_dir.join(name))));
+            }
+            _ => out.push_str(&after[..end]),
+        }
+        rest = &after[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeBase {
+    Light,
+    Dark,
+}
+
+/// Parse `#RGB` or `#RRGGBB` (leading `#` optional).
+pub fn parse_hex(hex: &str) -> Result<(u8, u8, u8), String> {
+    let digits = hex.trim().trim_start_matches('#');
+    let expanded: String = match digits.len() {
+        3 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 => digits.to_string(),
+        _ => return Err(format!("Invalid hex color: {}", hex)),
+    };
+    let channel = |i: usize| {
+        u8::from_str_radix(&expanded[i..i + 2], 16).map_err(|_| format!("Invalid hex color: {}", hex))
+    };
+    Ok((channel(0)?, channel(2)?, channel(4)?))
+}
+
+pub fn to_hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+/// RGB to HSL, with hue in degrees and saturation/lightness in 0..=1.
+fn rgb_to_hsl((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * (((g - b) / d).rem_euclid(6.0))
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    (h, s, l)
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match (h.rem_euclid(360.0) / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// Derive a full theme from one accent color. Surfaces keep the accent's hue at low
+/// saturation; lightness steps follow the built-in themes.
+pub fn generate_theme_from_accent(accent_hex: &str, base: ThemeBase) -> Result<ThemeDefinition, String> {
+    let accent = parse_hex(accent_hex)?;
+    let (h, s, l) = rgb_to_hsl(accent);
+    let tint = s.min(0.3);
+    let color = |sat: f64, light: f64| to_hex(hsl_to_rgb(h, sat, light));
+
+    // (bg primary, bg secondary, bg tertiary, input, border, muted, link, text)
+    let lightness = match base {
+        ThemeBase::Dark => [0.11, 0.13, 0.08, 0.09, 0.20, 0.37, 0.63, 0.93],
+        ThemeBase::Light => [0.97, 0.93, 0.99, 0.95, 0.82, 0.45, 0.40, 0.10],
+    };
+    let [bg1, bg2, bg3, input, border, muted, link, text] = lightness;
+
+    let accent_color = to_hex(accent);
+    let background = color(tint, bg1);
+    let border_color = color(tint, border);
+    let text_color = color(tint.min(0.2), text);
+
+    let variables: HashMap<String, String> = [
+        ("--rootsdk-brand-primary", accent_color.clone()),
+        ("--rootsdk-brand-secondary", color(s, l + 0.08)),
+        ("--rootsdk-brand-tertiary", color(s, l - 0.08)),
+        ("--rootsdk-background-primary", background.clone()),
+        ("--rootsdk-background-secondary", color(tint, bg2)),
+        ("--rootsdk-background-tertiary", color(tint, bg3)),
+        ("--rootsdk-input", color(tint, input)),
+        ("--rootsdk-border", border_color.clone()),
+        ("--rootsdk-link", color(s, link)),
+        ("--rootsdk-muted", color(tint.min(0.16), muted)),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+
+    let hex = accent_color.trim_start_matches('#').to_lowercase();
+    let base_name = match base {
+        ThemeBase::Dark => "dark",
+        ThemeBase::Light => "light",
+    };
+    Ok(ThemeDefinition {
+        name: format!("custom-{}", hex),
+        display_name: format!("Custom {}", accent_color),
+        description: format!("Generated {} theme from accent {}", base_name, accent_color),
+        author: String::new(),
+        tags: vec![base_name.to_string(), "custom".to_string()],
+        category: Some(base_name.to_string()),
+        extends: None,
+        variables,
+        preview_colors: PreviewColors {
+            background,
+            text: text_color,
+            accent: accent_color,
+            border: border_color,
+        },
+    })
+}
@@ -0,0 +1,233 @@
+use crate::embedded;
+use crate::hook::{embedded_artifacts, get_uprooted_dir};
+use crate::integrity::{self, from_hex, sha256_hex};
+use crate::settings::load_settings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Largest artifact we are willing to download.
+const MAX_ARTIFACT_BYTES: u64 = 64 * 1024 * 1024;
+/// Hex Ed25519 public key the update manifest must be signed with. Builds without
+/// one can't apply artifact updates.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("UPROOTED_UPDATE_PUBLIC_KEY");
+/// Hashes of the artifacts installed by `apply_artifact_updates()`, so deploys and
+/// repairs don't put the embedded versions back over them.
+const APPLIED_UPDATES_FILE: &str = "applied-updates.json";
+
+/// Upstream manifest served at `update_check_url`.
+#[derive(Deserialize)]
+struct UpstreamManifest {
+    artifacts: Vec<UpstreamArtifact>,
+}
+
+#[derive(Deserialize)]
+struct UpstreamArtifact {
+    name: String,
+    sha256: String,
+    url: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ArtifactUpdate {
+    pub name: String,
+    /// Hash of the applied update if one is deployed, else of the embedded artifact.
+    pub installed_sha256: String,
+    pub upstream_sha256: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub bytes_received: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Client that follows at most 10 redirects and refuses any that leave https.
+fn https_client() -> reqwest::Result<reqwest::blocking::Client> {
+    let policy = reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.url().scheme() != "https" {
+            let error = format!("refusing redirect to non-https URL {}", attempt.url());
+            attempt.error(error)
+        } else if attempt.previous().len() >= 10 {
+            attempt.error("too many redirects")
+        } else {
+            attempt.follow()
+        }
+    });
+    reqwest::blocking::Client::builder().redirect(policy).build()
+}
+
+/// Download `url` in chunks, reporting progress and enforcing `max_bytes`.
+pub fn download_with_progress(
+    url: &str,
+    max_bytes: u64,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<Vec<u8>, String> {
+    let mut response = https_client()
+        .and_then(|c| c.get(url).send())
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    let total = response.content_length();
+    if total.is_some_and(|t| t > max_bytes) {
+        return Err(format!("{} exceeds the {} byte limit", url, max_bytes));
+    }
+
+    let mut data = Vec::new();
+    let mut buf = [0u8; 16 * 1024];
+    loop {
+        let n = response
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+        if data.len() as u64 > max_bytes {
+            return Err(format!("{} exceeds the {} byte limit", url, max_bytes));
+        }
+        on_progress(data.len() as u64, total);
+    }
+    Ok(data)
+}
+
+fn require_https(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if parsed.scheme() != "https" {
+        return Err(format!("Only https:// URLs are allowed for updates, got {}", url));
+    }
+    Ok(())
+}
+
+/// Check `body` against the hex Ed25519 signature served at `<url>.sig`.
+fn verify_manifest_signature(url: &str, body: &[u8]) -> Result<(), String> {
+    use ring::signature::{UnparsedPublicKey, ED25519};
+
+    let key = UPDATE_PUBLIC_KEY
+        .and_then(from_hex)
+        .ok_or("This build has no update signing key, so artifact updates are disabled.")?;
+    let sig_url = format!("{}.sig", url.trim());
+    let sig = download_with_progress(&sig_url, 1024, |_, _| {})?;
+    let sig = std::str::from_utf8(&sig)
+        .ok()
+        .and_then(|s| from_hex(s.trim()))
+        .ok_or_else(|| format!("Invalid signature at {}", sig_url))?;
+    UnparsedPublicKey::new(&ED25519, key)
+        .verify(body, &sig)
+        .map_err(|_| "The update manifest signature does not verify.".to_string())
+}
+
+fn applied_updates(dir: &Path) -> HashMap<String, String> {
+    fs::read_to_string(dir.join(APPLIED_UPDATES_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Whether `dir/name` is an artifact update that is still intact, and so must not be
+/// overwritten with the embedded version.
+pub fn is_applied_update(dir: &Path, name: &str) -> bool {
+    applied_updates(dir).get(name).is_some_and(|sha256| {
+        fs::read(dir.join(name)).is_ok_and(|data| sha256_hex(&data) == *sha256)
+    })
+}
+
+/// Compare the signed upstream manifest against what is deployed: an applied update
+/// if one is intact, otherwise the embedded artifact (deploys may minify it, so the
+/// file on disk isn't hashed directly).
+pub fn check_for_artifact_updates() -> Result<Vec<ArtifactUpdate>, String> {
+    let url = load_settings().update_check_url;
+    if url.is_empty() {
+        return Ok(vec![]);
+    }
+    require_https(&url)?;
+
+    let body = download_with_progress(&url, 1024 * 1024, |_, _| {})?;
+    verify_manifest_signature(&url, &body)?;
+    let manifest: UpstreamManifest = serde_json::from_slice(&body)
+        .map_err(|e| format!("Invalid update manifest: {}", e))?;
+
+    let dir = get_uprooted_dir();
+    let mut updates = Vec::new();
+    for (name, data) in embedded_artifacts() {
+        let Some(upstream) = manifest.artifacts.iter().find(|a| a.name == name) else {
+            continue;
+        };
+        require_https(&upstream.url)?;
+        let installed_sha256 = match applied_updates(&dir).remove(name) {
+            Some(sha256) if is_applied_update(&dir, name) => sha256,
+            _ => sha256_hex(&embedded::decompress(data).map_err(|e| e.to_string())?),
+        };
+        if !upstream.sha256.eq_ignore_ascii_case(&installed_sha256) {
+            updates.push(ArtifactUpdate {
+                name: name.to_string(),
+                installed_sha256,
+                upstream_sha256: upstream.sha256.to_lowercase(),
+                url: upstream.url.clone(),
+            });
+        }
+    }
+    Ok(updates)
+}
+
+/// Download changed artifacts into a staging directory, verify them, then move them
+/// into the install directory. Nothing is replaced unless every download verifies.
+pub fn apply_artifact_updates(
+    mut on_progress: impl FnMut(DownloadProgress),
+) -> Result<Vec<String>, String> {
+    let updates = check_for_artifact_updates()?;
+    if updates.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let dir = get_uprooted_dir();
+    let staging = dir.join(".staging");
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+
+    let result = (|| {
+        for update in &updates {
+            let data = download_with_progress(&update.url, MAX_ARTIFACT_BYTES, |received, total| {
+                on_progress(DownloadProgress {
+                    name: update.name.clone(),
+                    bytes_received: received,
+                    total_bytes: total,
+                })
+            })?;
+            if sha256_hex(&data) != update.upstream_sha256 {
+                return Err(format!("Checksum mismatch for downloaded {}", update.name));
+            }
+            fs::write(staging.join(&update.name), &data)
+                .map_err(|e| format!("Failed to stage {}: {}", update.name, e))?;
+        }
+
+        let mut applied = Vec::new();
+        for update in &updates {
+            let target = dir.join(&update.name);
+            fs::copy(staging.join(&update.name), &target)
+                .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+            applied.push(update.name.clone());
+        }
+        Ok(applied)
+    })();
+
+    let _ = fs::remove_dir_all(&staging);
+    let applied = result?;
+
+    let mut recorded = applied_updates(&dir);
+    for update in &updates {
+        recorded.insert(update.name.clone(), update.upstream_sha256.clone());
+    }
+    let json = serde_json::to_string_pretty(&recorded)
+        .map_err(|e| format!("Failed to serialize {}: {}", APPLIED_UPDATES_FILE, e))?;
+    fs::write(dir.join(APPLIED_UPDATES_FILE), json)
+        .map_err(|e| format!("Failed to write {}: {}", APPLIED_UPDATES_FILE, e))?;
+
+    let names: Vec<&str> = embedded_artifacts().iter().map(|(name, _)| *name).collect();
+    integrity::write_manifest(&dir, &names)?;
+    Ok(applied)
+}
@@ -0,0 +1,5 @@
+//! Install, uninstall and repair against a mock Root profile on disk.
+
+mod mock_profile;
+mod patcher;
+mod patcher_roundtrip;
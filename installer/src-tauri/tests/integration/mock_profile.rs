@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use tempfile::TempDir;
+
+pub const ROOT_APPS: &[&str] = &["chat", "voice"];
+
+pub const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>Root</title>
+    <link rel="stylesheet" href="./assets/index-4f2a9c.css">
+    <script type="module" crossorigin src="./assets/index-b81d3e.js"></script>
+  </head>
+  <body>
+    <div id="root"></div>
+  </body>
+</html>
+"#;
+
+/// Serializes tests: the profile and install directories are chosen through
+/// process-wide environment variables.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// A directory laid out like a Root profile: `WebRtcBundle/index.html` and one
+/// `RootApps/<name>/index.html` per entry in `ROOT_APPS`.
+pub fn setup_mock_profile() -> TempDir {
+    let dir = tempfile::tempdir().expect("create temp profile");
+    write_index(&dir.path().join("WebRtcBundle"));
+    for app in ROOT_APPS {
+        write_index(&dir.path().join("RootApps").join(app));
+    }
+    dir
+}
+
+fn write_index(dir: &Path) {
+    fs::create_dir_all(dir).expect("create app dir");
+    fs::write(dir.join("index.html"), INDEX_HTML).expect("write index.html");
+}
+
+/// Point the installer at `profile` (and keep its install directory and env var
+/// files inside it) until the returned guard is dropped.
+pub fn activate(profile: &TempDir) -> MutexGuard<'static, ()> {
+    let guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let home = profile.path().join("home");
+    fs::create_dir_all(&home).expect("create home");
+    std::env::set_var("UPROOTED_PROFILE_DIR", profile.path());
+    std::env::set_var("UPROOTED_DIR", profile.path().join("uprooted"));
+    std::env::set_var("HOME", &home);
+    std::env::set_var("LOCALAPPDATA", &home);
+    guard
+}
+
+/// Contents of every target file, in a stable order.
+pub fn snapshot(profile: &TempDir) -> Vec<String> {
+    let mut paths = vec![profile.path().join("WebRtcBundle/index.html")];
+    paths.extend(
+        ROOT_APPS
+            .iter()
+            .map(|app| profile.path().join("RootApps").join(app).join("index.html")),
+    );
+    paths
+        .iter()
+        .map(|p| fs::read_to_string(p).expect("read target"))
+        .collect()
+}
@@ -0,0 +1,98 @@
+use crate::mock_profile::{activate, setup_mock_profile, snapshot, INDEX_HTML, ROOT_APPS};
+use uprooted_installer::patcher;
+
+#[test]
+fn install_patches_every_target() {
+    let profile = setup_mock_profile();
+    let _env = activate(&profile);
+
+    let result = patcher::install(false);
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.files_patched.len(), ROOT_APPS.len() + 1);
+    for content in snapshot(&profile) {
+        assert!(patcher::is_patched(&content));
+        assert!(content.contains("window.__UPROOTED_SETTINGS__"));
+    }
+    for file in &result.files_patched {
+        assert!(patcher::backup_path_for(file.as_ref()).exists());
+    }
+}
+
+#[test]
+fn install_twice_matches_install_once() {
+    let profile = setup_mock_profile();
+    let _env = activate(&profile);
+
+    assert!(patcher::install(false).success);
+    let once = snapshot(&profile);
+
+    let second = patcher::install(false);
+    assert!(second.success);
+    assert!(second.files_patched.is_empty());
+    assert_eq!(snapshot(&profile), once);
+}
+
+#[test]
+fn uninstall_restores_original_html() {
+    let profile = setup_mock_profile();
+    let _env = activate(&profile);
+
+    let installed = patcher::install(false);
+    assert!(installed.success);
+
+    let result = patcher::uninstall();
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.files_patched.len(), ROOT_APPS.len() + 1);
+    for content in snapshot(&profile) {
+        assert_eq!(content, INDEX_HTML);
+    }
+    for file in &installed.files_patched {
+        assert!(!patcher::backup_path_for(file.as_ref()).exists());
+    }
+}
+
+#[test]
+fn repair_leaves_a_single_injection() {
+    let profile = setup_mock_profile();
+    let _env = activate(&profile);
+
+    assert!(patcher::install(false).success);
+    let installed = snapshot(&profile);
+
+    let result = patcher::repair();
+    assert!(result.success, "{}", result.message);
+    let repaired = snapshot(&profile);
+    assert_eq!(repaired, installed);
+    for content in repaired {
+        assert_eq!(content.matches("uprooted:start").count(), 1);
+    }
+}
+
+#[test]
+fn strip_injection_recovers_original() {
+    let profile = setup_mock_profile();
+    let _env = activate(&profile);
+
+    assert!(patcher::install(false).success);
+    for content in snapshot(&profile) {
+        assert_eq!(patcher::strip_injection(&content), INDEX_HTML);
+    }
+}
+
+#[test]
+fn html_snapshot_brings_back_an_earlier_install() {
+    let profile = setup_mock_profile();
+    let _env = activate(&profile);
+
+    let result = patcher::install(false);
+    assert!(result.success, "{}", result.message);
+    let installed = snapshot(&profile);
+    let html = patcher::HtmlSnapshot::take();
+
+    assert!(patcher::uninstall().success);
+    html.restore().unwrap();
+    assert_eq!(snapshot(&profile), installed);
+    for file in &result.files_patched {
+        assert!(patcher::backup_path_for(file.as_ref()).exists());
+    }
+}
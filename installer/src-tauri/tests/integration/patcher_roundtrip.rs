@@ -0,0 +1,192 @@
+//! `install()` followed by `strip_injection()` must give back the original HTML
+//! byte for byte, whatever the surrounding markup looks like.
+
+use crate::mock_profile::activate;
+use proptest::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::MutexGuard;
+use tempfile::TempDir;
+use uprooted_installer::patcher;
+
+/// A fresh profile holding only `WebRtcBundle/index.html` with `html`, with the
+/// installer pointed at it until dropped.
+struct Profile {
+    dir: TempDir,
+    _env: MutexGuard<'static, ()>,
+}
+
+impl Profile {
+    fn new(html: &str) -> Self {
+        let dir = tempfile::tempdir().expect("create temp profile");
+        let env = activate(&dir);
+        let profile = Profile { dir, _env: env };
+        fs::create_dir_all(profile.index().parent().unwrap()).expect("create app dir");
+        fs::write(profile.index(), html).expect("write index.html");
+        profile
+    }
+
+    fn index(&self) -> PathBuf {
+        self.dir.path().join("WebRtcBundle").join("index.html")
+    }
+
+    fn read(&self) -> String {
+        fs::read_to_string(self.index()).expect("read index.html")
+    }
+}
+
+/// Install into `html` and check that stripping the result restores it.
+fn assert_round_trip(html: &str) -> Result<(), TestCaseError> {
+    let profile = Profile::new(html);
+    let result = patcher::install(false);
+    prop_assert!(result.success, "{}", result.message);
+
+    let patched = profile.read();
+    prop_assert!(patcher::is_patched(&patched));
+    prop_assert_eq!(patcher::strip_injection(&patched), html);
+    Ok(())
+}
+
+const INDENTS: &[&str] = &["", "  ", "    ", "\t", " \t "];
+
+const HEAD_CHILDREN: &[&str] = &[
+    "<meta charset=\"utf-8\">",
+    "<title>Root</title>",
+    "<script type=\"module\" crossorigin src=\"./assets/index-b81d3e.js\"></script>",
+    "<script>if (1 < 2 && 3 > 2) { window.ready = true; }</script>",
+    "<!-- build 2024-11-02 -->",
+    "<!--\n  multi-line\n  comment\n-->",
+    "<link rel=\"stylesheet\" href=\"./assets/index-4f2a9c.css\">",
+    "<style>\n  body { margin: 0; }\n</style>",
+];
+
+/// Twenty documents mixing indentation, comments, scripts, blank lines and a
+/// `</head>` that shares its line with other tags.
+fn synthetic_documents() -> Vec<String> {
+    (0..20)
+        .map(|i| {
+            let indent = INDENTS[i % INDENTS.len()];
+            let nl = if i % 7 == 6 { "" } else { "\n" };
+            let mut html = String::new();
+            if i % 3 != 0 {
+                html.push_str("<!DOCTYPE html>");
+                html.push_str(nl);
+            }
+            html.push_str("<html lang=\"en\">");
+            html.push_str(nl);
+            html.push_str(indent);
+            html.push_str("<head>");
+            html.push_str(nl);
+            for (j, child) in HEAD_CHILDREN.iter().enumerate().take(1 + i % HEAD_CHILDREN.len()) {
+                if (i + j) % 4 == 0 {
+                    html.push_str(nl);
+                }
+                html.push_str(indent);
+                html.push_str(indent);
+                html.push_str(child);
+                html.push_str(nl);
+            }
+            if i % 5 == 4 {
+                html.push_str("<title>inline</title>");
+            } else {
+                html.push_str(indent);
+            }
+            html.push_str("</head>");
+            html.push_str(nl);
+            html.push_str(indent);
+            html.push_str("<body><div id=\"root\"></div></body>");
+            html.push_str(nl);
+            html.push_str("</html>");
+            if i % 2 == 0 {
+                html.push_str(nl);
+            }
+            html
+        })
+        .collect()
+}
+
+#[test]
+fn synthetic_documents_round_trip() {
+    let documents = synthetic_documents();
+    assert_eq!(documents.len(), 20);
+    for html in documents {
+        assert_round_trip(&html).unwrap_or_else(|e| panic!("{}\n--- input ---\n{}", e, html));
+    }
+}
+
+#[test]
+fn install_on_patched_file_changes_nothing() {
+    for html in synthetic_documents() {
+        let profile = Profile::new(&html);
+        assert!(patcher::install(false).success);
+        let first = fs::read(profile.index()).unwrap();
+
+        let second = patcher::install(false);
+        assert!(second.success, "{}", second.message);
+        assert!(second.files_patched.is_empty());
+        assert_eq!(fs::read(profile.index()).unwrap(), first);
+    }
+}
+
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 .,;:!?'\"=/&-]{0,24}"
+}
+
+fn head_child() -> impl Strategy<Value = String> {
+    prop_oneof![
+        text().prop_map(|t| format!("<title>{}</title>", t)),
+        text().prop_map(|t| format!("<meta name=\"description\" content=\"{}\">", t)),
+        text().prop_map(|t| format!("<script>var s = \"{}\";</script>", t.replace('"', ""))),
+        text().prop_map(|t| format!("<!-- {} -->", t)),
+        text().prop_map(|t| format!("<!--\n{}\n-->", t)),
+        text().prop_map(|t| format!("<style>\n/* {} */\n</style>", t)),
+        Just("<link rel=\"stylesheet\" href=\"./a.css\">".to_string()),
+    ]
+}
+
+prop_compose! {
+    fn document()(
+        doctype in any::<bool>(),
+        indent in prop::sample::select(INDENTS),
+        children in prop::collection::vec((head_child(), prop::sample::select(INDENTS)), 0..8),
+        blank_lines in prop::collection::vec(0..3usize, 8),
+        close_inline in any::<bool>(),
+        body in text(),
+        minified in any::<bool>(),
+        trailing_newline in any::<bool>(),
+    ) -> String {
+        let nl = if minified { "" } else { "\n" };
+        let mut lines = Vec::new();
+        if doctype {
+            lines.push("<!DOCTYPE html>".to_string());
+        }
+        lines.push("<html>".to_string());
+        lines.push(format!("{}<head>", indent));
+        for (i, (child, child_indent)) in children.iter().enumerate() {
+            lines.extend(std::iter::repeat_n(String::new(), blank_lines[i]));
+            lines.push(format!("{}{}{}", indent, child_indent, child));
+        }
+        if close_inline && !lines.is_empty() {
+            let last = lines.pop().unwrap_or_default();
+            lines.push(format!("{}</head>", last));
+        } else {
+            lines.push(format!("{}</head>", indent));
+        }
+        lines.push(format!("{}<body>{}</body>", indent, body));
+        lines.push("</html>".to_string());
+        let mut html = lines.join(nl);
+        if trailing_newline {
+            html.push_str(nl);
+        }
+        html
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn generated_documents_round_trip(html in document()) {
+        assert_round_trip(&html)?;
+    }
+}
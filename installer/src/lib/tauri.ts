@@ -1,4 +1,5 @@
 const { invoke } = (window as any).__TAURI__.core;
+const { listen, emit } = (window as any).__TAURI__.event;
 
 export interface HookStatus {
   profiler_dll: boolean;
@@ -9,25 +10,96 @@ export interface HookStatus {
   env_enable_profiling: boolean;
   env_profiler_guid: boolean;
   env_profiler_path: boolean;
+  env_profiler_path_valid: boolean;
   env_ready_to_run: boolean;
+  env_startup_hook: boolean;
   files_ok: boolean;
   env_ok: boolean;
   env_vars_active: boolean;
+  tampered_files: string[];
+  dll_arch_mismatch: boolean;
+  installed_at: string | null;
+  installer_version: string | null;
+  last_repaired_at: string | null;
+  html_patching_disabled: boolean;
+  env_vars: EnvVarInfo[];
+  settings_enabled: boolean;
+  file_sizes: Record<string, number>;
+  files_possibly_truncated: boolean;
 }
 
+export interface EnvVarInfo {
+  key: string;
+  intended_value: string;
+  current_value: string | null;
+  is_set_correctly: boolean;
+  scope: string;
+}
+
+export interface FileIntegrity {
+  name: string;
+  expected_sha256: string;
+  actual_sha256: string | null;
+  ok: boolean;
+}
+
+/** Catches accidental corruption of the deployed files, not deliberate tampering. */
+export interface IntegrityReport {
+  manifest_found: boolean;
+  hmac_valid: boolean;
+  files: FileIntegrity[];
+  all_ok: boolean;
+}
+
+export type RootLayout = "AppImage" | "ExtractedAppImage" | "NativeBinary" | "Flatpak" | "Snap";
+
+export type UpdateMechanism = "Squirrel" | "Nsis" | "AppImage" | "Flatpak" | "Snap" | "Unknown";
+
 export interface DetectionResult {
   root_found: boolean;
   root_path: string;
   profile_dir: string;
   html_files: string[];
   is_installed: boolean;
+  is_snap: boolean;
   hook_status: HookStatus;
+  warnings: string[];
+  root_version: string | null;
+  compat_warning: string | null;
+  running_with_hook: boolean | null;
+  root_layout: RootLayout;
+  update_mechanism: UpdateMechanism;
+  apps_manifest_found: boolean;
+}
+
+export interface HtmlFileInfo {
+  path: string;
+  size_bytes: number;
+  modified: string;
+  is_patched: boolean;
+  injection_version: string | null;
+  backup_exists: boolean;
+}
+
+export type CheckSeverity = "Warning" | "Error";
+
+export interface PrerequisiteCheck {
+  name: string;
+  passed: boolean;
+  severity: CheckSeverity;
+  message: string;
 }
 
 export interface PatchResult {
   success: boolean;
   message: string;
   files_patched: string[];
+  recovery_hint: string | null;
+  error_code: number | null;
+  size_warning: string | null;
+  errors: string[];
+  warnings: string[];
+  csp_warning: string | null;
 }
 
 export interface PreviewColors {
@@ -42,69 +114,658 @@ export interface ThemeDefinition {
   display_name: string;
   description: string;
   author: string;
+  tags: string[];
+  category: string | null;
+  extends: string | null;
   variables: Record<string, string>;
   preview_colors: PreviewColors;
 }
 
+export interface ThemeQuery {
+  search?: string | null;
+  tags?: string[];
+  category?: string | null;
+}
+
 export interface PluginSettings {
   enabled: boolean;
   config: Record<string, unknown>;
+  priority: number;
+}
+
+export interface PluginManifest {
+  name: string;
+  version: string;
+  description: string;
+  author: string;
+  entry_js: string | null;
+  entry_css: string | null;
+  default_config: unknown;
+  config_schema: Record<string, unknown> | null;
+}
+
+export interface PluginInfo {
+  manifest: PluginManifest;
+  dir: string;
+  entry_js_path: string | null;
+  error: string | null;
+}
+
+export type HookMode = "Profiler" | "StartupHookOnly" | "EnvOnlyNoHtml";
+
+export type InstallScope = "User" | "System";
+
+export type BackupNaming = "Simple" | "Timestamped";
+
+export interface DesktopFileConfig {
+  displayName: string;
+  iconPath: string | null;
+  categories: string[];
+  comment: string | null;
 }
 
 export interface UprootedSettings {
   enabled: boolean;
   plugins: Record<string, PluginSettings>;
   customCss: string;
+  minifyCss: boolean;
+  detectionCacheTtlMs: number;
+  deployVerifySignatures: boolean;
+  startupCheck: boolean;
+  updateCheckUrl: string;
+  customTargets: string[];
+  disableRootBuiltinTheming: boolean;
+  hookMode: HookMode;
+  profilerGuid: string | null;
+  desktopFile: DesktopFileConfig;
+  firstRunSeen: boolean;
+  injectTiming: boolean;
+  installScope: InstallScope;
+  backupNaming: BackupNaming;
+  fixCsp: boolean;
+}
+
+/** What the startup auto-repair did, from `getStartupRepairResult()`. */
+export interface AutoRepairEvent {
+  success: boolean;
+  message: string;
+}
+
+/** Payload of the `uprooted://stage-ready` event. */
+export interface StageReady {
+  stage: string;
+  description: string;
+}
+
+export interface StageResult {
+  success: boolean;
+  message: string;
+  stages_completed: string[];
+  cancelled_at: string | null;
+  patch_result: PatchResult | null;
+}
+
+/** Payload of the `uprooted://upgraded` event. */
+export interface UpgradedEvent {
+  from_version: string;
+  to_version: string;
+  changelog: string;
+}
+
+export interface CssError {
+  line: number;
+  column: number;
+  message: string;
+}
+
+export interface BenchmarkResult {
+  original_bytes: number;
+  minified_bytes: number;
+  iterations: number;
+  average_micros: number;
 }
 
 export async function detectRoot(): Promise<DetectionResult> {
   return invoke("detect_root");
 }
 
+export async function invalidateDetectionCache(): Promise<void> {
+  return invoke("invalidate_detection_cache");
+}
+
+export async function listTargetFiles(): Promise<HtmlFileInfo[]> {
+  return invoke("list_target_files");
+}
+
+export interface RootAppInfo {
+  app_dir: string;
+  index_html: string;
+  app_name: string;
+  is_patched: boolean;
+  injection_version: string | null;
+}
+
+export async function listRootApps(): Promise<RootAppInfo[]> {
+  return invoke("list_root_apps");
+}
+
+export interface InjectionPreview {
+  injection_html: string;
+  injection_bytes: number;
+  target_files: string[];
+}
+
+export interface ForeignInjection {
+  framework_name: string;
+  marker_found: string;
+}
+
+export interface ForeignInjectionReport {
+  file: string;
+  injection: ForeignInjection;
+}
+
+export async function scanForForeignInjections(): Promise<ForeignInjectionReport[]> {
+  return invoke("scan_for_foreign_injections");
+}
+
+export async function previewInjectionString(): Promise<InjectionPreview> {
+  return invoke("preview_injection_string");
+}
+
+/** Write the injection to a highlighted HTML page and return its path. */
+export async function dumpInjectionToFile(): Promise<string> {
+  return invoke("dump_injection_to_file");
+}
+
+export async function openInjectionDebug(): Promise<void> {
+  return invoke("open_injection_debug");
+}
+
+export async function checkPrerequisites(): Promise<PrerequisiteCheck[]> {
+  return invoke("check_prerequisites");
+}
+
+export type InstallState =
+  | { state: "NotInstalled" }
+  | { state: "InstalledCurrentVersion" }
+  | { state: "InstalledOlderVersion"; version: string }
+  | { state: "InstalledNewerVersion"; version: string };
+
+export interface LegacyInstallInfo {
+  found: boolean;
+  locations: string[];
+}
+
+export async function detectLegacyInstall(): Promise<LegacyInstallInfo> {
+  return invoke("detect_legacy_install");
+}
+
+export async function migrateFromLegacy(): Promise<void> {
+  return invoke("migrate_from_legacy");
+}
+
+export async function getInstallState(): Promise<InstallState> {
+  return invoke("get_install_state");
+}
+
+/** Label of the install, repair or uninstall in progress, or null when idle. */
+export async function getCurrentOperation(): Promise<string | null> {
+  return invoke("get_current_operation");
+}
+
 export async function installUprooted(): Promise<PatchResult> {
   return invoke("install_uprooted");
 }
 
+export async function installUprootedTolerant(): Promise<PatchResult> {
+  return invoke("install_uprooted_tolerant");
+}
+
+export async function patchFile(path: string): Promise<PatchResult> {
+  return invoke("patch_file", { path });
+}
+
+export async function unpatchFile(path: string): Promise<PatchResult> {
+  return invoke("unpatch_file", { path });
+}
+
 export async function uninstallUprooted(): Promise<PatchResult> {
   return invoke("uninstall_uprooted");
 }
 
+export interface PurgeReport {
+  steps_completed: string[];
+  errors: string[];
+  bytes_freed: number;
+}
+
+export async function purgeAllUprootedData(): Promise<PurgeReport> {
+  return invoke("purge_all_uprooted_data");
+}
+
 export async function repairUprooted(): Promise<PatchResult> {
   return invoke("repair_uprooted");
 }
 
+export async function getPendingRepair(): Promise<boolean> {
+  return invoke("get_pending_repair");
+}
+
+export interface ArtifactUpdate {
+  name: string;
+  installed_sha256: string;
+  upstream_sha256: string;
+  url: string;
+}
+
+/** Payload of the `uprooted://download-progress` event. */
+export interface DownloadProgress {
+  name: string;
+  bytes_received: number;
+  total_bytes: number | null;
+}
+
+export async function checkForArtifactUpdates(): Promise<ArtifactUpdate[]> {
+  return invoke("check_for_artifact_updates");
+}
+
+export async function applyArtifactUpdates(): Promise<string[]> {
+  return invoke("apply_artifact_updates");
+}
+
 export async function loadSettings(): Promise<UprootedSettings> {
   return invoke("load_settings");
 }
 
+export interface DiffEntry {
+  path: string;
+  default_value: unknown;
+  current_value: unknown;
+}
+
+export interface SettingsDiff {
+  changed_fields: DiffEntry[];
+}
+
+export async function getSettingsDiff(): Promise<SettingsDiff> {
+  return invoke("get_settings_diff");
+}
+
+export async function getSettingsOverridePath(): Promise<string | null> {
+  return invoke("get_settings_override_path");
+}
+
+/** `UPROOTED_SETTINGS_PATH` when it names a missing file, so default settings are in use. */
+export async function getMissingSettingsOverride(): Promise<string | null> {
+  return invoke("get_missing_settings_override");
+}
+
+/** Result of the auto-repair run before the window opened; returned once, then null. */
+export async function getStartupRepairResult(): Promise<AutoRepairEvent | null> {
+  return invoke("get_startup_repair_result");
+}
+
 export async function saveSettings(settings: UprootedSettings): Promise<void> {
   return invoke("save_settings", { settings });
 }
 
+/** Set a single setting by JSON Pointer, e.g. `/plugins/themes/config/theme`. */
+export async function saveSettingsField(fieldPath: string, value: unknown): Promise<void> {
+  return invoke("save_settings_field", { fieldPath, value });
+}
+
+export async function validateProfilerGuid(guid: string): Promise<void> {
+  return invoke("validate_profiler_guid", { guid });
+}
+
+export async function setDesktopFileConfig(config: DesktopFileConfig): Promise<void> {
+  return invoke("set_desktop_file_config", { config });
+}
+
+export async function validateCustomCss(css: string): Promise<void> {
+  return invoke("validate_custom_css", { css });
+}
+
+/** Debug builds only; release builds don't register the command. */
+export async function benchmarkCssMinification(): Promise<BenchmarkResult> {
+  return invoke("benchmark_css_minification");
+}
+
+export async function listAvailablePlugins(): Promise<PluginInfo[]> {
+  return invoke("list_available_plugins");
+}
+
+export async function getPluginConfigSchema(pluginName: string): Promise<Record<string, unknown> | null> {
+  return invoke("get_plugin_config_schema", { pluginName });
+}
+
 export async function listThemes(): Promise<ThemeDefinition[]> {
   return invoke("list_themes");
 }
 
+export interface ThemeDiff {
+  only_in_a: Record<string, string>;
+  only_in_b: Record<string, string>;
+  changed: Record<string, [string, string]>;
+  identical_count: number;
+  preview_diff: { changed_fields: string[] };
+}
+
+export async function compareThemes(nameA: string, nameB: string): Promise<ThemeDiff> {
+  return invoke("compare_themes", { nameA, nameB });
+}
+
+export async function filterThemes(query: ThemeQuery): Promise<ThemeDefinition[]> {
+  return invoke("filter_themes", { query });
+}
+
+export interface ContrastWarning {
+  pair: [string, string];
+  ratio: number;
+  required: number;
+}
+
+export interface ImportResult {
+  theme: ThemeDefinition;
+  warnings: ContrastWarning[];
+}
+
+export async function importTheme(json: string): Promise<ImportResult> {
+  return invoke("import_theme", { json });
+}
+
+export async function importThemeFromUrl(url: string): Promise<ThemeDefinition> {
+  return invoke("import_theme_from_url", { url });
+}
+
+export async function importThemeZip(path: string): Promise<ImportResult> {
+  return invoke("import_theme_zip", { path });
+}
+
+export async function themeAssetPath(themeName: string, asset: string): Promise<string> {
+  return invoke("theme_asset_path", { themeName, asset });
+}
+
+export async function validateThemeContrast(
+  theme: ThemeDefinition,
+): Promise<ContrastWarning[]> {
+  return invoke("validate_theme_contrast", { theme });
+}
+
+export type ThemeBase = "Light" | "Dark";
+
+export async function generateThemeFromAccent(
+  accentHex: string,
+  base: ThemeBase,
+): Promise<ThemeDefinition> {
+  return invoke("generate_theme_from_accent", { accentHex, base });
+}
+
 export async function applyTheme(name: string): Promise<void> {
   return invoke("apply_theme", { name });
 }
 
+export async function resetPluginConfig(pluginName: string): Promise<void> {
+  return invoke("reset_plugin_config", { pluginName });
+}
+
+export async function resetAllPlugins(): Promise<void> {
+  return invoke("reset_all_plugins");
+}
+
+export async function togglePlugin(name: string, enabled: boolean): Promise<void> {
+  return invoke("toggle_plugin", { name, enabled });
+}
+
 export async function getUprootedVersion(): Promise<string> {
   return invoke("get_uprooted_version");
 }
 
+export interface DiskUsage {
+  uprooted_dir_bytes: number;
+  backup_files_bytes: number;
+  profile_snapshot_bytes: number;
+  log_files_bytes: number;
+  total_bytes: number;
+}
+
+export async function getDiskUsage(): Promise<DiskUsage> {
+  return invoke("get_disk_usage");
+}
+
+export interface ArtifactVersions {
+  installer: string;
+  preload_js: string;
+  theme_css: string;
+}
+
+export async function getArtifactVersions(): Promise<ArtifactVersions> {
+  return invoke("get_artifact_versions");
+}
+
+export async function getArtifactSize(name: string): Promise<number | null> {
+  return invoke("get_artifact_size", { name });
+}
+
+export async function getChangelog(): Promise<string> {
+  return invoke("get_changelog");
+}
+
+export async function getVersionChangelog(version: string): Promise<string | null> {
+  return invoke("get_version_changelog", { version });
+}
+
+/** One line of `uprooted.jsonl`. */
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  message: string;
+  module: string;
+  fields: Record<string, unknown>;
+}
+
+/** The newest `limit` structured log entries, oldest first. */
+export async function parseLogEntries(limit: number): Promise<LogEntry[]> {
+  return invoke("parse_log_entries", { limit });
+}
+
 export async function openProfileDir(): Promise<void> {
   return invoke("open_profile_dir");
 }
 
+export async function openUprootedDir(): Promise<void> {
+  return invoke("open_uprooted_dir");
+}
+
+export async function openSettingsDir(): Promise<void> {
+  return invoke("open_settings_dir");
+}
+
+export interface SystemInfo {
+  os: string;
+  arch: string;
+  root_version: string | null;
+  dotnet_version: string | null;
+  is_snap: boolean;
+  in_wsl: boolean;
+}
+
+export async function getSystemInfo(): Promise<SystemInfo> {
+  return invoke("get_system_info");
+}
+
+/** Write a Markdown description of uprooted's changes on this machine to `outputPath`. */
+export async function generateInstallDocs(outputPath: string): Promise<void> {
+  return invoke("generate_install_docs", { outputPath });
+}
+
 export async function checkHookStatus(): Promise<HookStatus> {
   return invoke("check_hook_status");
 }
 
+export async function listEnvVars(): Promise<EnvVarInfo[]> {
+  return invoke("list_env_vars");
+}
+
+export type HookKind = "ClrProfiler" | "StartupHook" | "JsPreload" | "StyleSheet" | "EnvironmentVar";
+
+export interface HookEntry {
+  kind: HookKind;
+  name: string;
+  path: string;
+  is_active: boolean;
+  details: Record<string, string>;
+}
+
+export async function getActiveHooks(): Promise<HookEntry[]> {
+  return invoke("get_active_hooks");
+}
+
+/** Re-check the hook status every `intervalMs` (at least 250); changes arrive through `onHookStatusChanged`. */
+export async function startHookStatusPoll(intervalMs: number): Promise<void> {
+  return invoke("start_hook_status_poll", { intervalMs });
+}
+
+export async function stopHookStatusPoll(): Promise<void> {
+  return invoke("stop_hook_status_poll");
+}
+
+export async function onHookStatusChanged(handler: (status: HookStatus) => void): Promise<void> {
+  await listen("uprooted://hook-status-changed", (e: { payload: HookStatus }) => handler(e.payload));
+}
+
+export type HookSeverity = "Ok" | "Info" | "Warning" | "Critical";
+
+export interface HookSeverityReport {
+  overall: HookSeverity;
+  per_field: Record<string, HookSeverity>;
+}
+
+export async function getHookSeverity(): Promise<HookSeverityReport> {
+  return invoke("get_hook_severity");
+}
+
+export interface HealthIssue {
+  description: string;
+  severity: CheckSeverity;
+  fix_command: string | null;
+}
+
+export interface HealthScore {
+  score: number;
+  max_score: number;
+  grade: string;
+  issues: HealthIssue[];
+}
+
+export async function getHealthScore(): Promise<HealthScore> {
+  return invoke("get_health_score");
+}
+
+export interface HookVersionInfo {
+  profiler_version: string | null;
+  hook_dll_version: string | null;
+  preload_js_hash: string | null;
+  theme_css_hash: string | null;
+  drifted: string[];
+}
+
+export async function getHookVersions(): Promise<HookVersionInfo> {
+  return invoke("get_hook_versions");
+}
+
+export async function verifyDeployedIntegrity(): Promise<IntegrityReport> {
+  return invoke("verify_deployed_integrity");
+}
+
+export async function isFirstRun(): Promise<boolean> {
+  return invoke("is_first_run");
+}
+
 export async function checkRootRunning(): Promise<boolean> {
   return invoke("check_root_running");
 }
 
+export async function isReadOnly(): Promise<boolean> {
+  return invoke("is_read_only");
+}
+
+export async function repairFile(fileName: string): Promise<void> {
+  return invoke("repair_file", { fileName });
+}
+
+export async function relaunchViaWrapper(): Promise<void> {
+  return invoke("relaunch_via_wrapper");
+}
+
+/** Fires when Root starts without the hook env vars while uprooted is installed. */
+export async function onHookInactive(handler: () => void): Promise<void> {
+  await listen("uprooted://hook-inactive", handler);
+}
+
+/**
+ * Install one stage at a time. `onStage` is asked before each stage; returning
+ * false cancels the install and rolls back the stages already done.
+ */
+export async function installStaged(onStage: (stage: StageReady) => Promise<boolean>): Promise<StageResult> {
+  const unlisten = await listen("uprooted://stage-ready", async (e: { payload: StageReady }) => {
+    const proceed = await onStage(e.payload);
+    await emit("uprooted://stage-confirm", { proceed });
+  });
+  try {
+    return await invoke("install_staged");
+  } finally {
+    unlisten();
+  }
+}
+
+export async function onUpgraded(handler: (event: UpgradedEvent) => void): Promise<void> {
+  await listen("uprooted://upgraded", (e: { payload: UpgradedEvent }) => handler(e.payload));
+}
+
 export async function killRoot(): Promise<number> {
   return invoke("kill_root");
 }
+
+export interface ServiceStatus {
+  installed: boolean;
+  active: boolean;
+  state: string;
+}
+
+export async function installSystemdService(): Promise<void> {
+  return invoke("install_systemd_service");
+}
+
+export async function uninstallSystemdService(): Promise<void> {
+  return invoke("uninstall_systemd_service");
+}
+
+export async function getServiceStatus(): Promise<ServiceStatus> {
+  return invoke("get_service_status");
+}
+
+export async function installScheduledTask(): Promise<void> {
+  return invoke("install_scheduled_task");
+}
+
+export async function removeScheduledTask(): Promise<void> {
+  return invoke("remove_scheduled_task");
+}
+
+export async function isScheduledTaskInstalled(): Promise<boolean> {
+  return invoke("is_scheduled_task_installed");
+}
+
+export async function installAutostart(): Promise<void> {
+  return invoke("install_autostart");
+}
+
+export async function removeAutostart(): Promise<void> {
+  return invoke("remove_autostart");
+}
+
+export async function isAutostartEnabled(): Promise<boolean> {
+  return invoke("is_autostart_enabled");
+}
@@ -5,12 +5,31 @@ import {
   repairUprooted,
   getUprootedVersion,
   checkRootRunning,
+  isReadOnly,
+  getCurrentOperation,
+  isFirstRun,
+  checkPrerequisites,
+  saveSettingsField,
   killRoot,
+  relaunchViaWrapper,
+  onHookInactive,
+  onUpgraded,
+  loadSettings,
+  saveSettings,
+  invalidateDetectionCache,
+  getHealthScore,
+  getStartupRepairResult,
+  getMissingSettingsOverride,
   type DetectionResult,
+  type PatchResult,
+  type UpgradedEvent,
 } from "../lib/tauri.js";
 
 let logEl: HTMLDivElement;
 let detection: DetectionResult | null = null;
+let readOnly = false;
+/** Set while an install, repair or uninstall started earlier is still running. */
+let busy = false;
 const isLinux = navigator.platform.startsWith("Linux");
 const rootExeName = isLinux ? "Root" : "root.exe";
 
@@ -24,6 +43,14 @@ function log(text: string, type: "info" | "success" | "error" | "warn" | "" = ""
   logEl.scrollTop = logEl.scrollHeight;
 }
 
+function logFailure(result: PatchResult): void {
+  const code = result.error_code ? ` [E${result.error_code}]` : "";
+  log(`${result.message}${code}`, "error");
+  if (result.recovery_hint) {
+    log(`  ${result.recovery_hint}`, "warn");
+  }
+}
+
 function logBlank(): void {
   const line = document.createElement("div");
   line.className = "log-line";
@@ -89,6 +116,19 @@ async function runDetection(): Promise<void> {
 
   // Hook files
   const hs = detection.hook_status;
+  document.getElementById("disabled-banner")!.hidden = hs.settings_enabled;
+  if (!hs.settings_enabled) {
+    log("uprooted is disabled in settings -- install and repair do nothing", "warn");
+  }
+  readOnly = await isReadOnly().catch(() => false);
+  if (readOnly) {
+    log("read-only mode -- the install folders are not writable by this user", "warn");
+  }
+  const running = await getCurrentOperation().catch(() => null);
+  busy = running !== null;
+  if (running) {
+    log(`still running: ${running.toLowerCase()}`, "warn");
+  }
   if (hs.files_ok) {
     log("hook files: all deployed", "success");
   } else {
@@ -104,6 +144,9 @@ async function runDetection(): Promise<void> {
       log(`hook files: partial (missing: ${missing.join(", ")})`, "warn");
     }
   }
+  if (hs.files_possibly_truncated) {
+    log("hook files: some are smaller than expected -- run repair to redeploy them", "warn");
+  }
 
   // Environment variables
   if (hs.env_ok) {
@@ -113,12 +156,8 @@ async function runDetection(): Promise<void> {
       log("env vars: configured", "success");
     }
   } else {
-    const missing: string[] = [];
-    if (!hs.env_enable_profiling) missing.push("CORECLR_ENABLE_PROFILING");
-    if (!hs.env_profiler_guid) missing.push("CORECLR_PROFILER");
-    if (!hs.env_profiler_path) missing.push("CORECLR_PROFILER_PATH");
-    if (!hs.env_ready_to_run) missing.push("DOTNET_ReadyToRun");
-    if (missing.length === 4) {
+    const missing = hs.env_vars.filter((v) => !v.is_set_correctly).map((v) => v.key);
+    if (missing.length === hs.env_vars.length) {
       log("env vars: not configured");
     } else {
       log(`env vars: partial (missing: ${missing.join(", ")})`, "warn");
@@ -139,6 +178,7 @@ async function runDetection(): Promise<void> {
 
   updateStatusDisplay();
   updateButtons();
+  await updateHealthGauge();
 }
 
 function analyzeScenario(): void {
@@ -169,13 +209,16 @@ function analyzeScenario(): void {
 
   // Partial install scenarios
   const hasAnyFiles = hs.profiler_dll || hs.hook_dll || hs.hook_deps || hs.preload_js || hs.theme_css;
-  const hasAnyEnv = hs.env_enable_profiling || hs.env_profiler_guid || hs.env_profiler_path;
+  const hasAnyEnv =
+    hs.env_enable_profiling || hs.env_profiler_guid || hs.env_profiler_path || hs.env_startup_hook;
 
   if (hasAnyFiles && !hs.files_ok) {
     log("warning: hook files are partially deployed -- try repair", "warn");
   }
 
-  if (hasAnyEnv && !hs.env_ok) {
+  if (hs.env_profiler_path && !hs.env_profiler_path_valid) {
+    log("warning: CORECLR_PROFILER_PATH points at a different profiler -- try repair", "warn");
+  } else if (hasAnyEnv && !hs.env_ok) {
     log("warning: environment variables are partially configured -- try repair", "warn");
   }
 
@@ -231,6 +274,30 @@ function updateStatusDisplay(): void {
   `;
 }
 
+async function updateHealthGauge(): Promise<void> {
+  const el = document.getElementById("status-rows");
+  if (!el) return;
+
+  let health;
+  try {
+    health = await getHealthScore();
+  } catch {
+    return;
+  }
+  const percent = Math.round((health.score / health.max_score) * 100);
+  const color = percent >= 90 ? "green" : percent >= 60 ? "yellow" : "red";
+  el.insertAdjacentHTML(
+    "beforeend",
+    `
+    <div class="status-row" title="${escapeHtml(health.issues.map((i) => i.description).join("\n"))}">
+      ${statusDot(color)}
+      <span class="status-label">Health</span>
+      <span class="health-gauge"><span class="health-fill ${color}" style="width: ${percent}%"></span></span>
+      <span class="status-value">${percent}% (${health.grade})</span>
+    </div>`,
+  );
+}
+
 // ── Button state ──
 
 function updateButtons(): void {
@@ -242,16 +309,18 @@ function updateButtons(): void {
 
   const isInstalled = detection.is_installed || detection.hook_status.files_ok || detection.hook_status.env_ok;
 
+  const locked = readOnly || busy;
+
   if (installBtn) {
-    installBtn.disabled = !detection.root_found || isInstalled;
+    installBtn.disabled = locked || !detection.root_found || isInstalled;
     installBtn.classList.remove("loading");
   }
   if (uninstallBtn) {
-    uninstallBtn.disabled = !isInstalled;
+    uninstallBtn.disabled = locked || !isInstalled;
     uninstallBtn.classList.remove("loading");
   }
   if (repairBtn) {
-    repairBtn.disabled = !isInstalled;
+    repairBtn.disabled = locked || !isInstalled;
     repairBtn.classList.remove("loading");
   }
 }
@@ -364,10 +433,19 @@ async function handleInstall(): Promise<void> {
       for (const f of result.files_patched) {
         log(`  patched: ${fileName(f)}`, "success");
       }
+      if (result.size_warning) {
+        log(result.size_warning, "warn");
+      }
+      if (result.csp_warning) {
+        log(result.csp_warning, "warn");
+      }
+      for (const w of result.warnings) {
+        log(w, "warn");
+      }
       logBlank();
       log("restart root to activate uprooted", "success");
     } else {
-      log(result.message, "error");
+      logFailure(result);
     }
     await runDetection();
   } catch (err) {
@@ -396,7 +474,7 @@ async function handleUninstall(): Promise<void> {
         log(`  restored: ${fileName(f)}`, "success");
       }
     } else {
-      log(result.message, "error");
+      logFailure(result);
     }
     await runDetection();
   } catch (err) {
@@ -421,8 +499,14 @@ async function handleRepair(): Promise<void> {
     logBlank();
     if (result.success) {
       log(result.message, "success");
+      if (result.size_warning) {
+        log(result.size_warning, "warn");
+      }
+      if (result.csp_warning) {
+        log(result.csp_warning, "warn");
+      }
     } else {
-      log(result.message, "error");
+      logFailure(result);
     }
     await runDetection();
   } catch (err) {
@@ -450,6 +534,101 @@ function copyLogs(): void {
   });
 }
 
+async function handleEnable(): Promise<void> {
+  try {
+    const settings = await loadSettings();
+    await saveSettings({ ...settings, enabled: true });
+    log("uprooted enabled", "success");
+    await invalidateDetectionCache();
+    await runDetection();
+  } catch (err) {
+    log(`failed to enable uprooted: ${err}`, "error");
+  }
+}
+
+async function handleRelaunch(): Promise<void> {
+  setButtonLoading("btn-relaunch");
+  try {
+    await relaunchViaWrapper();
+    document.getElementById("hook-banner")!.hidden = true;
+    log("relaunched root with uprooted", "success");
+  } catch (err) {
+    log(`relaunch failed: ${err}`, "error");
+  } finally {
+    const btn = document.getElementById("btn-relaunch") as HTMLButtonElement;
+    btn.disabled = false;
+    btn.classList.remove("loading");
+  }
+}
+
+// ── Welcome ──
+
+async function showWelcome(): Promise<void> {
+  const overlay = document.createElement("div");
+  overlay.className = "popup-overlay";
+  overlay.innerHTML = `
+    <div class="popup">
+      <div class="popup-text">welcome to uprooted</div>
+      <div class="popup-sub">root was found and nothing is installed yet</div>
+      <div class="welcome-checks">checking prerequisites...</div>
+      <div class="popup-actions">
+        <button class="btn primary popup-install" hidden>install now</button>
+        <button class="btn popup-skip">not now</button>
+      </div>
+    </div>
+  `;
+  document.body.appendChild(overlay);
+
+  const checksEl = overlay.querySelector(".welcome-checks") as HTMLDivElement;
+  const installBtn = overlay.querySelector(".popup-install") as HTMLButtonElement;
+
+  installBtn.addEventListener("click", async () => {
+    overlay.remove();
+    await handleInstall();
+  });
+  overlay.querySelector(".popup-skip")!.addEventListener("click", async () => {
+    overlay.remove();
+    try {
+      await saveSettingsField("/firstRunSeen", true);
+    } catch (err) {
+      log(`failed to save settings: ${err}`, "error");
+    }
+  });
+
+  try {
+    const checks = await checkPrerequisites();
+    const failed = checks.filter((c) => !c.passed);
+    checksEl.innerHTML = failed.length === 0
+      ? `<div class="log-line success">all ${checks.length} checks passed</div>`
+      : failed
+        .map((c) => {
+          const type = c.severity === "Error" ? "error" : "warn";
+          return `<div class="log-line ${type}">${escapeHtml(c.name)}: ${escapeHtml(c.message)}</div>`;
+        })
+        .join("");
+    installBtn.hidden = failed.some((c) => c.severity === "Error");
+  } catch (err) {
+    checksEl.textContent = `prerequisite check failed: ${err}`;
+  }
+}
+
+function showWhatsNew(event: UpgradedEvent): void {
+  const overlay = document.createElement("div");
+  overlay.className = "popup-overlay";
+  overlay.innerHTML = `
+    <div class="popup">
+      <div class="popup-text">what's new</div>
+      <div class="popup-sub">upgraded from ${escapeHtml(event.from_version)} to ${escapeHtml(event.to_version)}</div>
+      <pre class="changelog">${escapeHtml(event.changelog)}</pre>
+      <div class="popup-actions">
+        <button class="btn popup-close">close</button>
+      </div>
+    </div>
+  `;
+  document.body.appendChild(overlay);
+  overlay.querySelector(".popup-close")!.addEventListener("click", () => overlay.remove());
+}
+
 // ── Init ──
 
 export async function init(container: HTMLElement): Promise<void> {
@@ -476,6 +655,16 @@ export async function init(container: HTMLElement): Promise<void> {
       </div>
     </div>
 
+    <div id="disabled-banner" class="hook-banner" hidden>
+      <span>uprooted is disabled</span>
+      <button id="btn-enable" class="btn warn">enable</button>
+    </div>
+
+    <div id="hook-banner" class="hook-banner" hidden>
+      <span>root is running without uprooted -- mods are not loaded</span>
+      <button id="btn-relaunch" class="btn warn">relaunch via wrapper</button>
+    </div>
+
     <div class="actions">
       <button id="btn-install" class="btn primary" disabled>install</button>
       <button id="btn-uninstall" class="btn danger" disabled>uninstall</button>
@@ -498,6 +687,29 @@ export async function init(container: HTMLElement): Promise<void> {
   document.getElementById("btn-uninstall")!.addEventListener("click", handleUninstall);
   document.getElementById("btn-repair")!.addEventListener("click", handleRepair);
   document.getElementById("btn-copy-log")!.addEventListener("click", copyLogs);
+  document.getElementById("btn-relaunch")!.addEventListener("click", handleRelaunch);
+  document.getElementById("btn-enable")!.addEventListener("click", handleEnable);
+
+  await onHookInactive(() => {
+    document.getElementById("hook-banner")!.hidden = false;
+    log("root started without uprooted's environment variables", "warn");
+  });
+  await onUpgraded((event) => {
+    if (event.changelog) showWhatsNew(event);
+  });
+  const missingOverride = await getMissingSettingsOverride().catch(() => null);
+  if (missingOverride) {
+    log(`UPROOTED_SETTINGS_PATH points to ${missingOverride}, which does not exist -- using default settings`, "warn");
+  }
+
+  const repaired = await getStartupRepairResult().catch(() => null);
+  if (repaired) {
+    log(`startup check: ${repaired.message}`, repaired.success ? "success" : "error");
+  }
 
   await runDetection();
+
+  if (detection?.root_found && !detection.is_installed && (await isFirstRun().catch(() => false))) {
+    await showWelcome();
+  }
 }
@@ -1,13 +1,17 @@
 import {
   listThemes,
   applyTheme,
+  importTheme,
   loadSettings,
+  type ContrastWarning,
   type ThemeDefinition,
 } from "../lib/tauri.js";
 
 let themes: ThemeDefinition[] = [];
 let activeTheme = "default";
 let selectedTheme: ThemeDefinition | null = null;
+let importMessage = "";
+let importWarnings: ContrastWarning[] = [];
 let container: HTMLElement;
 
 function renderThemeCard(theme: ThemeDefinition): string {
@@ -63,6 +67,20 @@ function renderDetail(theme: ThemeDefinition): string {
   `;
 }
 
+function renderImportHints(): string {
+  if (!importMessage) return "";
+  const hints = importWarnings
+    .map(
+      (w) =>
+        `<li>${w.pair[0]} on ${w.pair[1]}: contrast ${w.ratio}:1 (needs ${w.required}:1)</li>`,
+    )
+    .join("");
+  return `
+    <p class="notice">${importMessage}</p>
+    ${hints ? `<ul class="notice">accessibility hints:${hints}</ul>` : ""}
+  `;
+}
+
 function render(): void {
   const grid = themes.map(renderThemeCard).join("");
   const detail = selectedTheme ? renderDetail(selectedTheme) : "";
@@ -77,9 +95,33 @@ function render(): void {
 
     ${detail}
 
+    <div class="theme-import">
+      <button id="btn-import-theme" class="btn">import theme</button>
+      <input id="theme-file" type="file" accept=".json,application/json" hidden />
+    </div>
+    ${renderImportHints()}
+
     <p class="notice">restart root to apply theme changes.</p>
   `;
 
+  const fileInput = container.querySelector<HTMLInputElement>("#theme-file")!;
+  container.querySelector("#btn-import-theme")?.addEventListener("click", () => fileInput.click());
+  fileInput.addEventListener("change", async () => {
+    const file = fileInput.files?.[0];
+    if (!file) return;
+    try {
+      const result = await importTheme(await file.text());
+      themes = themes.filter((t) => t.name !== result.theme.name).concat(result.theme);
+      selectedTheme = result.theme;
+      importMessage = `imported ${result.theme.display_name}`;
+      importWarnings = result.warnings;
+    } catch (err) {
+      importMessage = `import failed: ${err}`;
+      importWarnings = [];
+    }
+    render();
+  });
+
   // Bind click handlers
   for (const card of container.querySelectorAll<HTMLElement>(".theme-card")) {
     card.addEventListener("click", async () => {
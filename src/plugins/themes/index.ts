@@ -43,6 +43,18 @@ for (const name of customVarNames) {
   allVarNames.add(name);
 }
 
+/**
+ * Turn `$name` references in variable values into native `var(--name)` lookups,
+ * so a theme can say `"--background-hover": "$background"`.
+ */
+function expandReferences(vars: Record<string, string>): Record<string, string> {
+  const expanded: Record<string, string> = {};
+  for (const [name, value] of Object.entries(vars)) {
+    expanded[name] = value.replace(/\$([\w-]+)/g, "var(--$1)");
+  }
+  return expanded;
+}
+
 // ===== Color math helpers =====
 
 function parseHex(hex: string): [number, number, number] {
@@ -133,7 +145,7 @@ export default {
 
     const theme = (themes as ThemeDef[]).find((t) => t.name === themeName);
     if (theme && Object.keys(theme.variables).length > 0) {
-      setCssVariables(theme.variables);
+      setCssVariables(expandReferences(theme.variables));
     }
   },
 
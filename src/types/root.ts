@@ -23,6 +23,8 @@ declare global {
     // Uprooted injections
     __UPROOTED_SETTINGS__: UprootedSettings;
     __UPROOTED_VERSION__: string;
+    /** Written by the installer when it patched the page; absent if `injectTiming` is off. */
+    __UPROOTED_META__?: { version: string; injected_at: string };
     __UPROOTED_LOADER__: import("../core/pluginLoader.js").PluginLoader;
   }
 }